@@ -1,15 +1,27 @@
-use std::time::Instant;
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use crate::{
-    builder::{builder::Builder, nuitka::NuitkaBuilder, pyinstaller::PyInstallerBuilder},
+    builder::{
+        builder::Builder, metadata::ProductMetadata, nuitka::NuitkaBuilder,
+        pyinstaller::PyInstallerBuilder,
+    },
     cache::{Cache, load_cache, save_cache},
     cli::{Backend, BuildOptions, BuildStage},
     errcode::{Errcode, GeneralErrorKind, ToolchainErrorKind},
     files::Files,
+    layout::Layout,
     pyproject::PyProjectConfig,
-    qt::{assets::compile_resources, i18n::compile_i18n_ts_files, ui::convert_ui_files},
+    qt::{
+        assets::{binary_resource_paths, compile_resources},
+        i18n::compile_i18n_ts_files,
+        ui::convert_ui_files,
+    },
     toolchain::Toolchain,
     utils::format_duration,
+    version::{VersionInfo, VersionSource},
 };
 
 pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
@@ -20,7 +32,18 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
             target: opt.target,
         }));
     };
-    let files = Files::new(target_path);
+    let layout = pyproject_config
+        .layouts
+        .get(&opt.target)
+        .cloned()
+        .unwrap_or_default();
+    let mut files = Files::new(target_path, &layout, &pyproject_config.file_filters);
+    if let Some(runtime) = pyproject_config.ui.runtime.get(&opt.target) {
+        let ui_dir = layout.ui_dir(target_path);
+        files.split_runtime_forms(|form| {
+            runtime.contains(form.strip_prefix(&ui_dir).unwrap_or(form))
+        });
+    }
     let mut cache: Cache = if opt.no_cache {
         Cache::default()
     } else {
@@ -42,7 +65,7 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
         };
         log::info!("Compiling i18n files...");
         let start = Instant::now();
-        compile_i18n_ts_files(target_path, &lrelease, &files, &mut cache)?;
+        compile_i18n_ts_files(target_path, &layout, &lrelease, &files, &mut cache)?;
         log::info!(
             "I18n files compiled in {}.",
             format_duration(start.elapsed())
@@ -59,7 +82,14 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
         };
         log::info!("Converting ui files...");
         let start = Instant::now();
-        convert_ui_files(target_path, &uic, &files, &mut cache)?;
+        convert_ui_files(
+            target_path,
+            &layout,
+            &pyproject_config.ui,
+            &uic,
+            &files,
+            &mut cache,
+        )?;
         log::info!(
             "Ui files converted in {}.",
             format_duration(start.elapsed())
@@ -71,21 +101,17 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
         opt.stage,
         BuildStage::Assets | BuildStage::Rc | BuildStage::All
     ) {
-        let rcc = match &toolchain.rcc {
-            Some(rcc) => rcc.clone(),
-            None => {
-                return Err(Errcode::ToolchainError(ToolchainErrorKind::RccNotFound));
-            }
-        };
-        let git = match &toolchain.git {
-            Some(git) => git.clone(),
-            None => {
-                return Err(Errcode::ToolchainError(ToolchainErrorKind::GitNotFound));
-            }
-        };
         log::info!("Compiling assets...");
         let start = Instant::now();
-        compile_resources(target_path, &rcc, &git, &files, &mut cache)?;
+        compile_resources(
+            target_path,
+            &layout,
+            &pyproject_config.assets,
+            &pyproject_config.version,
+            &toolchain,
+            &files,
+            &mut cache,
+        )?;
         log::info!("Assets compiled in {}.", format_duration(start.elapsed()));
     }
 
@@ -98,8 +124,16 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
 
     // Build via backend
     if matches!(opt.stage, BuildStage::Build | BuildStage::All) {
-        let build_type = opt.resolve_build_type();
-        let backend: Box<dyn Builder> = match &opt.backend {
+        let build_type = opt.resolve_build_type(pyproject_config.build_type.as_ref());
+        let backend_kind = opt.resolve_backend(pyproject_config.backend.as_ref());
+        let data_files = collect_data_files(target_path, &layout, &pyproject_config, &files)?;
+        let metadata = resolve_metadata(&opt.target, &pyproject_config, &toolchain)?;
+        log::info!(
+            "Building {} version {}.",
+            metadata.product_name,
+            metadata.version
+        );
+        let backend: Box<dyn Builder> = match backend_kind {
             Backend::Nuitka => {
                 let nuitka_exe = toolchain
                     .nuitka
@@ -108,22 +142,21 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
 
                 let mut extra_opts = opt.backend_args;
                 extra_opts.extend(pyproject_config.extra_nuitka_options_list);
+                extra_opts.extend(
+                    data_files
+                        .iter()
+                        .map(|f| format!("--include-data-files={0}={0}", to_slash(f))),
+                );
 
                 #[cfg(target_os = "macos")]
                 {
                     use crate::builder::nuitka::mac::BundleInfo;
-                    use crate::qt::assets::get_last_tag;
-
-                    let git_exe = toolchain
-                        .git
-                        .clone()
-                        .ok_or(Errcode::ToolchainError(ToolchainErrorKind::GitNotFound))?;
-
-                    let version = get_last_tag(&git_exe, "0.0.0.0");
 
+                    // Bundle versions only take numbers, e.g. `1.2.0` for `1.2.0.post3+gabc1234`
+                    let [major, minor, patch, _] = metadata.numeric_version();
                     let bundle_info = BundleInfo {
                         name: opt.target.clone(),
-                        version,
+                        version: format!("{}.{}.{}", major, minor, patch),
                     };
 
                     let builder = NuitkaBuilder::new(
@@ -132,6 +165,7 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
                         &nuitka_exe,
                         build_type,
                         extra_opts,
+                        &metadata,
                         bundle_info,
                     )?;
 
@@ -146,6 +180,7 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
                         &nuitka_exe,
                         build_type,
                         extra_opts,
+                        &metadata,
                     )?;
 
                     Box::new(builder)
@@ -163,6 +198,10 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
                 };
                 let mut extra_opts = opt.backend_args;
                 extra_opts.extend(pyproject_config.extra_pyinstaller_options_list);
+                extra_opts.extend(data_files.iter().map(|f| {
+                    let dest = f.parent().unwrap_or(Path::new("."));
+                    format!("--add-data={}:{}", to_slash(f), to_slash(dest))
+                }));
 
                 let builder = PyInstallerBuilder::new(
                     &opt.target,
@@ -170,6 +209,7 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
                     &pyinstaller_exe,
                     build_type,
                     extra_opts,
+                    &metadata,
                 )?;
 
                 Box::new(builder)
@@ -186,3 +226,56 @@ pub fn action(opt: BuildOptions) -> Result<(), Errcode> {
 
     Ok(())
 }
+
+/// Product metadata of `target`, versioned from the configured version source.
+fn resolve_metadata(
+    target: &str,
+    config: &PyProjectConfig,
+    toolchain: &Toolchain,
+) -> Result<ProductMetadata, Errcode> {
+    let git = toolchain.git.as_deref();
+    if git.is_none() && config.version.source == VersionSource::Git {
+        log::warn!(
+            "Git not found, using fallback version {}.",
+            config.version.fallback_version()
+        );
+    }
+    let version = VersionInfo::resolve(&config.version, git)?;
+
+    Ok(config
+        .metadata
+        .get(target)
+        .cloned()
+        .unwrap_or_default()
+        .resolve(target, &version.pep440()))
+}
+
+/// Generated files that must ship with the app, at the same relative path.
+fn collect_data_files(
+    root: &Path,
+    layout: &Layout,
+    config: &PyProjectConfig,
+    files: &Files,
+) -> Result<Vec<PathBuf>, Errcode> {
+    let mut data_files = Vec::new();
+
+    if config.assets.output.binary() {
+        for rcc_file in binary_resource_paths(root, layout, &config.assets, files)? {
+            if rcc_file.exists() {
+                log::info!("Including binary resource bundle {}.", rcc_file.display());
+                data_files.push(rcc_file);
+            } else {
+                log::warn!(
+                    "Binary resource bundle {} not found, run the assets stage first.",
+                    rcc_file.display()
+                );
+            }
+        }
+    }
+
+    Ok(data_files)
+}
+
+fn to_slash(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
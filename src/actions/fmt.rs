@@ -0,0 +1,81 @@
+use std::{
+    fs,
+    io::{self, Write},
+};
+
+use crate::{
+    actions::target_files,
+    cli::{FmtCommand, FmtOptions, FmtUiOptions},
+    errcode::{Errcode, GeneralErrorKind},
+    pyproject::PyProjectConfig,
+    qt::ui_fmt::format_ui,
+};
+
+pub fn action(opt: FmtOptions) -> Result<(), Errcode> {
+    match opt.command {
+        FmtCommand::Ui(opt) => fmt_ui(opt),
+    }
+}
+
+fn fmt_ui(opt: FmtUiOptions) -> Result<(), Errcode> {
+    let ui_list = if opt.files.is_empty() {
+        let pyproject_config = PyProjectConfig::new("pyproject.toml".into())?;
+        target_files(&pyproject_config, &opt.target)?.1.ui_list
+    } else {
+        opt.files
+    };
+
+    // Everything is formatted first, an invalid form leaves all files untouched
+    let mut unformatted = Vec::new();
+    for ui_file in &ui_list {
+        let xml = fs::read_to_string(ui_file).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+                path: ui_file.clone(),
+                source: e,
+            })
+        })?;
+        let formatted = format_ui(&xml).map_err(|msg| {
+            Errcode::GeneralError(GeneralErrorKind::UiInvalid {
+                path: ui_file.clone(),
+                msg,
+            })
+        })?;
+        if formatted != xml {
+            unformatted.push((ui_file, formatted));
+        }
+    }
+
+    let changed = unformatted.len();
+    let mut out = io::stdout().lock();
+    for (ui_file, formatted) in unformatted {
+        if opt.check {
+            writeln!(out, "{}", ui_file.display()).unwrap();
+            continue;
+        }
+        fs::write(ui_file, formatted).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+                path: ui_file.clone(),
+                source: e,
+            })
+        })?;
+        log::info!("Formatted {}.", ui_file.display());
+    }
+
+    if opt.check && changed > 0 {
+        return Err(Errcode::GeneralError(GeneralErrorKind::FormatCheckFailed {
+            count: changed,
+        }));
+    }
+
+    log::info!(
+        "{} of {} file(s) {}.",
+        changed,
+        ui_list.len(),
+        if opt.check {
+            "need formatting"
+        } else {
+            "formatted"
+        }
+    );
+    Ok(())
+}
@@ -1,16 +1,31 @@
-use std::time::Instant;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::Instant,
+};
+
+use comfy_table::{CellAlignment, Table, presets::UTF8_FULL};
+use serde::Serialize;
 
 use crate::{
-    cli::I18nOptions,
+    actions::target_files,
+    cli::{I18nCommand, I18nOptions, I18nStatusOptions, StatusFormat},
     errcode::{Errcode, GeneralErrorKind},
-    files::Files,
     pyproject::PyProjectConfig,
-    qt::i18n::generate_i18n_ts_files,
+    qt::i18n::{TsStatus, generate_i18n_ts_files, parse_ts_status},
     toolchain::Toolchain,
     utils::format_duration,
 };
 
 pub fn action(opt: I18nOptions) -> Result<(), Errcode> {
+    match opt.command {
+        None => generate(&opt.target),
+        Some(I18nCommand::Status(opt)) => status(opt),
+    }
+}
+
+fn generate(target: &str) -> Result<(), Errcode> {
     let toolchain = Toolchain::new();
     let lupdate = match &toolchain.lupdate {
         Some(lupdate) => lupdate.clone(),
@@ -20,17 +35,16 @@ pub fn action(opt: I18nOptions) -> Result<(), Errcode> {
         }
     };
     let pyproject_config = PyProjectConfig::new("pyproject.toml".into())?;
-    let Some(root) = &pyproject_config.scripts.get(&opt.target) else {
-        return Err(Errcode::GeneralError(GeneralErrorKind::TargetNotFound {
-            target: opt.target,
-        }));
-    };
-
-    let files = Files::new(root);
+    let (root, files) = target_files(&pyproject_config, target)?;
+    let layout = pyproject_config
+        .layouts
+        .get(target)
+        .cloned()
+        .unwrap_or_default();
 
     log::info!("Generating i18n files...");
     let start = Instant::now();
-    generate_i18n_ts_files(root, &lupdate, &files, pyproject_config.languages)?;
+    generate_i18n_ts_files(&root, &layout, &lupdate, &files, pyproject_config.languages)?;
     log::info!(
         "I18n files generated in {}.",
         format_duration(start.elapsed())
@@ -38,3 +52,110 @@ pub fn action(opt: I18nOptions) -> Result<(), Errcode> {
 
     Ok(())
 }
+
+#[derive(Serialize)]
+struct StatusRow {
+    path: PathBuf,
+    #[serde(flatten)]
+    status: TsStatus,
+    completeness: f64,
+}
+
+fn status(opt: I18nStatusOptions) -> Result<(), Errcode> {
+    let pyproject_config = PyProjectConfig::new("pyproject.toml".into())?;
+    let (_, files) = target_files(&pyproject_config, &opt.target)?;
+
+    let mut rows = Vec::new();
+    for ts_file in files
+        .i18n_list
+        .iter()
+        .filter(|f| f.extension().is_some_and(|ext| ext == "ts"))
+    {
+        let xml = fs::read_to_string(ts_file).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+                path: ts_file.clone(),
+                source: e,
+            })
+        })?;
+        let stem = ts_file
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let status = parse_ts_status(&xml, &stem).map_err(|msg| {
+            Errcode::GeneralError(GeneralErrorKind::TsInvalid {
+                path: ts_file.clone(),
+                msg,
+            })
+        })?;
+        rows.push(StatusRow {
+            path: ts_file.clone(),
+            completeness: status.completeness(),
+            status,
+        });
+    }
+    rows.sort_by(|a, b| a.status.language.cmp(&b.status.language));
+
+    let mut out = io::stdout().lock();
+    match opt.format {
+        StatusFormat::Table => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL).set_header(vec![
+                "Language",
+                "File",
+                "Finished",
+                "Unfinished",
+                "Vanished",
+                "Obsolete",
+                "Completeness",
+            ]);
+            for row in &rows {
+                table.add_row(vec![
+                    row.status.language.clone(),
+                    row.path.display().to_string(),
+                    row.status.finished.to_string(),
+                    row.status.unfinished.to_string(),
+                    row.status.vanished.to_string(),
+                    row.status.obsolete.to_string(),
+                    format!("{:.1}%", row.completeness),
+                ]);
+            }
+            for column in 2..7 {
+                if let Some(column) = table.column_mut(column) {
+                    column.set_cell_alignment(CellAlignment::Right);
+                }
+            }
+            writeln!(out, "{table}").unwrap();
+        }
+        StatusFormat::Json => {
+            writeln!(out, "{}", serde_json::to_string_pretty(&rows).unwrap()).unwrap();
+        }
+    }
+
+    if rows.is_empty() {
+        log::warn!("No translation files found, run `pyside-cli i18n` first.");
+    }
+
+    let Some(threshold) = opt.min_completeness.or(pyproject_config.min_completeness) else {
+        return Ok(());
+    };
+    let incomplete: Vec<String> = rows
+        .iter()
+        .filter(|row| row.completeness < threshold)
+        .map(|row| format!("{} ({:.1}%)", row.status.language, row.completeness))
+        .collect();
+    if !incomplete.is_empty() {
+        return Err(Errcode::GeneralError(
+            GeneralErrorKind::TranslationsIncomplete {
+                threshold,
+                languages: incomplete.join(", "),
+            },
+        ));
+    }
+
+    log::info!(
+        "All {} language(s) are at least {}% complete.",
+        rows.len(),
+        threshold
+    );
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::{
+    actions::target_files,
+    cli::{LintCommand, LintOptions, LintUiOptions, ReportFormat},
+    errcode::{Errcode, GeneralErrorKind},
+    files::Files,
+    layout::Layout,
+    pyproject::PyProjectConfig,
+    qt::{
+        a11y::audit_ui_form,
+        asset_index::AssetIndex,
+        asset_usage::{audit_asset_usage, find_asset_references, find_index_references},
+        assets::{resource_entries, resource_index},
+        lint::{Diagnostic, LintContext, Severity, lint_ui_form},
+        ui_form::UiForm,
+    },
+    utils::format_size,
+};
+
+/// Files that can refer to assets by their resource path.
+const REFERENCING_EXTENSIONS: &[&str] = &["py", "ui", "qml", "qss"];
+
+pub fn action(opt: LintOptions) -> Result<(), Errcode> {
+    match opt.command {
+        LintCommand::Ui(opt) => lint_ui(opt),
+        LintCommand::A11y(opt) => lint_a11y(opt),
+        LintCommand::Assets(opt) => lint_assets(opt),
+    }
+}
+
+fn lint_ui(opt: LintUiOptions) -> Result<(), Errcode> {
+    let pyproject_config = PyProjectConfig::new("pyproject.toml".into())?;
+    let (root, files) = target_files(&pyproject_config, &opt.target)?;
+    let layout = pyproject_config
+        .layouts
+        .get(&opt.target)
+        .cloned()
+        .unwrap_or_default();
+    let resources = resource_index(&root, &layout, &pyproject_config.assets, &files)?;
+    let context = LintContext {
+        resources: &resources,
+        module_exists: &python_module_exists,
+    };
+
+    let diagnostics = check_ui_files(&files.ui_list, |path, form| {
+        lint_ui_form(path, form, &context)
+    })?;
+    report(&diagnostics, files.ui_list.len(), None, &opt)
+}
+
+fn lint_a11y(opt: LintUiOptions) -> Result<(), Errcode> {
+    let pyproject_config = PyProjectConfig::new("pyproject.toml".into())?;
+    let (_, files) = target_files(&pyproject_config, &opt.target)?;
+
+    let diagnostics = check_ui_files(&files.ui_list, audit_ui_form)?;
+    report(&diagnostics, files.ui_list.len(), None, &opt)
+}
+
+fn lint_assets(opt: LintUiOptions) -> Result<(), Errcode> {
+    let pyproject_config = PyProjectConfig::new("pyproject.toml".into())?;
+    let (root, files) = target_files(&pyproject_config, &opt.target)?;
+    let layout = pyproject_config
+        .layouts
+        .get(&opt.target)
+        .cloned()
+        .unwrap_or_default();
+    let entries = resource_entries(&root, &layout, &pyproject_config.assets, &files)?;
+    // Sources may use the constants of the asset index instead of paths
+    let resources: BTreeSet<String> = entries.iter().map(|e| e.resource.clone()).collect();
+    let index = AssetIndex::new(&resources);
+
+    let mut references = Vec::new();
+    for source in asset_referencing_files(&root, &layout, &files) {
+        let content = fs::read_to_string(&source).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+                path: source.clone(),
+                source: e,
+            })
+        })?;
+        let mut found = find_asset_references(&content);
+        if source.extension().is_some_and(|ext| ext == "py") {
+            found.extend(find_index_references(&content, &index));
+            found.sort_by_key(|r| r.line);
+        }
+        references.push((source, found));
+    }
+
+    let file_size = |path: &Path| fs::metadata(path).map_or(0, |m| m.len());
+    let usage = audit_asset_usage(&entries, &files.asset_list, &references, &file_size);
+    if usage.unused > 0 {
+        log::info!(
+            "{} unused asset(s), {} could be saved.",
+            usage.unused,
+            format_size(usage.unused_size)
+        );
+    }
+    report(
+        &usage.diagnostics,
+        references.len(),
+        Some(usage.unused_size),
+        &opt,
+    )
+}
+
+/// Python sources, forms, QML and style sheets of a target, generated
+/// modules excluded.
+fn asset_referencing_files(root: &Path, layout: &Layout, files: &Files) -> Vec<PathBuf> {
+    let is_source = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| REFERENCING_EXTENSIONS.contains(&ext))
+    };
+
+    let mut sources: BTreeSet<PathBuf> = files
+        .source_list
+        .iter()
+        .chain(&files.ui_list)
+        .chain(files.asset_list.iter().filter(|a| is_source(a)))
+        .cloned()
+        .collect();
+
+    // `Files` only knows QML and style sheets inside the assets directory
+    let skipped = [
+        layout.resources_dir(root),
+        layout.test_dir(root),
+        layout.assets_dir(root),
+    ];
+    sources.extend(
+        WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| !skipped.iter().any(|dir| e.path() == dir))
+            .filter_map(Result::ok)
+            .filter(|e| {
+                e.file_type().is_file()
+                    && e.path()
+                        .extension()
+                        .is_some_and(|ext| ext == "qml" || ext == "qss")
+            })
+            .map(|e| e.into_path()),
+    );
+    sources.into_iter().collect()
+}
+
+/// Parses every form and runs `check` on it, unparsable forms are errors.
+fn check_ui_files(
+    ui_list: &[PathBuf],
+    check: impl Fn(&Path, &UiForm) -> Vec<Diagnostic>,
+) -> Result<Vec<Diagnostic>, Errcode> {
+    let mut diagnostics = Vec::new();
+    for ui_file in ui_list {
+        let xml = fs::read_to_string(ui_file).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+                path: ui_file.clone(),
+                source: e,
+            })
+        })?;
+        match UiForm::parse(&xml) {
+            Ok(form) => diagnostics.extend(check(ui_file, &form)),
+            Err(msg) => diagnostics.push(Diagnostic {
+                path: ui_file.clone(),
+                line: 1,
+                severity: Severity::Error,
+                code: "invalid-ui",
+                message: msg,
+            }),
+        }
+    }
+    Ok(diagnostics)
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    checked: usize,
+    errors: usize,
+    warnings: usize,
+    /// Bytes taken by unused assets, only for `lint assets`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unused_size: Option<u64>,
+    diagnostics: &'a [Diagnostic],
+}
+
+/// Prints the diagnostics and fails on errors, or on any diagnostic with `--strict`.
+fn report(
+    diagnostics: &[Diagnostic],
+    checked: usize,
+    unused_size: Option<u64>,
+    opt: &LintUiOptions,
+) -> Result<(), Errcode> {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    let mut out = io::stdout().lock();
+    match opt.format {
+        ReportFormat::Text => {
+            for diagnostic in diagnostics {
+                writeln!(out, "{}", diagnostic).unwrap();
+            }
+        }
+        ReportFormat::Json => {
+            let report = Report {
+                checked,
+                errors,
+                warnings,
+                unused_size,
+                diagnostics,
+            };
+            writeln!(out, "{}", serde_json::to_string_pretty(&report).unwrap()).unwrap();
+        }
+    }
+
+    if errors > 0 || (opt.strict && warnings > 0) {
+        return Err(Errcode::GeneralError(GeneralErrorKind::LintFailed {
+            errors,
+            warnings,
+        }));
+    }
+
+    log::info!("Checked {} file(s): {} warning(s).", checked, warnings);
+    Ok(())
+}
+
+/// Whether `module` can be imported from the project root, e.g. `app.widgets.plot`.
+fn python_module_exists(module: &str) -> bool {
+    if module == "PySide6" || module.starts_with("PySide6.") {
+        return true;
+    }
+
+    let path = Path::new(".").join(module.replace('.', "/"));
+    path.with_extension("py").is_file() || path.join("__init__.py").is_file()
+}
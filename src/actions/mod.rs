@@ -1,6 +1,33 @@
 pub mod build;
 pub mod completions;
 pub mod create;
+pub mod fmt;
 pub mod i18n;
+pub mod lint;
 pub mod targets;
 pub mod test;
+pub mod ui;
+
+use std::path::PathBuf;
+
+use crate::{
+    errcode::{Errcode, GeneralErrorKind},
+    files::Files,
+    pyproject::PyProjectConfig,
+};
+
+/// Root package and files of `target` from `[project.scripts]`.
+pub(crate) fn target_files(
+    config: &PyProjectConfig,
+    target: &str,
+) -> Result<(PathBuf, Files), Errcode> {
+    let Some(root) = config.scripts.get(target) else {
+        return Err(Errcode::GeneralError(GeneralErrorKind::TargetNotFound {
+            target: target.to_string(),
+        }));
+    };
+
+    let layout = config.layouts.get(target).cloned().unwrap_or_default();
+    let files = Files::new(root, &layout, &config.file_filters);
+    Ok((root.clone(), files))
+}
@@ -0,0 +1,66 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    cli::{UiCommand, UiDiffOptions, UiOptions},
+    errcode::{Errcode, GeneralErrorKind},
+    qt::{ui_diff::diff_ui_forms, ui_form::UiForm},
+};
+
+pub fn action(opt: UiOptions) -> Result<(), Errcode> {
+    match opt.command {
+        UiCommand::Diff(opt) => diff(opt),
+    }
+}
+
+fn diff(opt: UiDiffOptions) -> Result<(), Errcode> {
+    // git external diffs get `path old-file old-hex old-mode new-file new-hex new-mode`
+    let (label, old, new) = match opt.files.as_slice() {
+        [old, new] => (format!("{} -> {}", old.display(), new.display()), old, new),
+        [path, old, _, _, new, _, _] => (path.display().to_string(), old, new),
+        files => {
+            return Err(Errcode::GeneralError(
+                GeneralErrorKind::DiffArgumentsInvalid { count: files.len() },
+            ));
+        }
+    };
+
+    let mut out = io::stdout().lock();
+    match (read_form(old)?, read_form(new)?) {
+        (None, None) => {}
+        (None, Some(form)) => writeln!(out, "{}: added form {}", label, form.class_name).unwrap(),
+        (Some(form), None) => writeln!(out, "{}: removed form {}", label, form.class_name).unwrap(),
+        (Some(old), Some(new)) => {
+            let diff = diff_ui_forms(&old, &new);
+            if diff.is_empty() {
+                writeln!(out, "{}: no changes", label).unwrap();
+            } else {
+                write!(out, "{}\n{}", label, diff).unwrap();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads a form, `None` for the `/dev/null` git passes for added and deleted files.
+fn read_form(path: &Path) -> Result<Option<UiForm>, Errcode> {
+    if path == Path::new("/dev/null") {
+        return Ok(None);
+    }
+
+    let xml = fs::read_to_string(path).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    })?;
+    UiForm::parse(&xml).map(Some).map_err(|msg| {
+        Errcode::GeneralError(GeneralErrorKind::UiInvalid {
+            path: path.to_path_buf(),
+            msg,
+        })
+    })
+}
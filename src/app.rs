@@ -25,6 +25,9 @@ pub fn run() -> Result<(), Errcode> {
         Command::I18n(opt) => actions::i18n::action(opt)?,
         Command::Build(opt) => actions::build::action(opt)?,
         Command::Test(opt) => actions::test::action(opt)?,
+        Command::Lint(opt) => actions::lint::action(opt)?,
+        Command::Ui(opt) => actions::ui::action(opt)?,
+        Command::Fmt(opt) => actions::fmt::action(opt)?,
         Command::Create { name } => actions::create::action(name)?,
         Command::Completions { shell } => actions::completions::action(shell),
     }
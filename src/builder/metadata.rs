@@ -0,0 +1,234 @@
+use serde::Deserialize;
+
+use crate::utils::py_str;
+
+/// Product information embedded into the built executable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProductMetadata {
+    pub product_name: String,
+    pub version: String,
+    pub company: Option<String>,
+    pub description: Option<String>,
+    pub copyright: Option<String>,
+}
+
+/// Partial metadata as written in `[tool.pyside-cli.metadata]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetadataOverrides {
+    pub product_name: Option<String>,
+    pub version: Option<String>,
+    pub company: Option<String>,
+    pub description: Option<String>,
+    pub copyright: Option<String>,
+}
+
+impl MetadataOverrides {
+    /// Fills unset fields from `fallback`.
+    pub fn or(&self, fallback: &MetadataOverrides) -> Self {
+        let pick = |value: &Option<String>, fallback: &Option<String>| {
+            value.clone().or_else(|| fallback.clone())
+        };
+
+        Self {
+            product_name: pick(&self.product_name, &fallback.product_name),
+            version: pick(&self.version, &fallback.version),
+            company: pick(&self.company, &fallback.company),
+            description: pick(&self.description, &fallback.description),
+            copyright: pick(&self.copyright, &fallback.copyright),
+        }
+    }
+
+    /// Completes the metadata, `product_name` and `version` are used when
+    /// they are not configured.
+    pub fn resolve(&self, product_name: &str, version: &str) -> ProductMetadata {
+        ProductMetadata {
+            product_name: self
+                .product_name
+                .clone()
+                .unwrap_or_else(|| product_name.to_string()),
+            version: self.version.clone().unwrap_or_else(|| version.to_string()),
+            company: self.company.clone(),
+            description: self.description.clone(),
+            copyright: self.copyright.clone(),
+        }
+    }
+}
+
+impl ProductMetadata {
+    /// Windows version resources only take up to four numbers,
+    /// e.g. `1.2.0rc1.post3+gabc1234` becomes `1.2.0.0`.
+    ///
+    /// Numbers above 65535 do not fit and are capped, with a warning.
+    pub fn numeric_version(&self) -> [u16; 4] {
+        let version = self.version.trim_start_matches(['v', 'V']);
+        let mut numbers = [0; 4];
+        for (slot, part) in numbers.iter_mut().zip(version.split('.')) {
+            let digits_len = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            if digits_len == 0 {
+                break;
+            }
+            let digits = &part[..digits_len];
+            *slot = digits.parse().unwrap_or_else(|_| {
+                log::warn!(
+                    "Version number {} of {} is larger than 65535, using 65535 in the version resource.",
+                    digits,
+                    self.version
+                );
+                u16::MAX
+            });
+            if digits_len < part.len() {
+                break;
+            }
+        }
+        numbers
+    }
+
+    fn numeric_version_string(&self) -> String {
+        self.numeric_version()
+            .iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Nuitka version information options.
+    ///
+    /// Options already present in `existing`, e.g. set in the backend options
+    /// of pyproject.toml, are left out.
+    pub fn nuitka_options(&self, existing: &[String]) -> Vec<String> {
+        let numeric_version = self.numeric_version_string();
+        let candidates = [
+            ("product-name", Some(&self.product_name)),
+            ("product-version", Some(&numeric_version)),
+            ("file-version", Some(&numeric_version)),
+            ("company-name", self.company.as_ref()),
+            ("file-description", self.description.as_ref()),
+            ("copyright", self.copyright.as_ref()),
+        ];
+
+        candidates
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let flag = format!("--{}", key);
+                let configured = existing
+                    .iter()
+                    .any(|opt| opt == &flag || opt.starts_with(&format!("{}=", flag)));
+                if configured {
+                    log::debug!("Keeping configured {}.", flag);
+                    return None;
+                }
+                value.map(|value| format!("{}={}", flag, value))
+            })
+            .collect()
+    }
+
+    /// Contents of a PyInstaller `--version-file`.
+    ///
+    /// Ref: https://pyinstaller.org/en/stable/usage.html#capturing-windows-version-data
+    pub fn pyinstaller_version_file(&self, target_name: &str) -> String {
+        let numbers = self.numeric_version();
+        let tuple = format!(
+            "({}, {}, {}, {})",
+            numbers[0], numbers[1], numbers[2], numbers[3]
+        );
+
+        let mut strings = Vec::new();
+        if let Some(company) = &self.company {
+            strings.push(("CompanyName", company.clone()));
+        }
+        strings.push((
+            "FileDescription",
+            self.description
+                .clone()
+                .unwrap_or_else(|| self.product_name.clone()),
+        ));
+        strings.push(("FileVersion", self.version.clone()));
+        strings.push(("InternalName", target_name.to_string()));
+        if let Some(copyright) = &self.copyright {
+            strings.push(("LegalCopyright", copyright.clone()));
+        }
+        strings.push(("OriginalFilename", format!("{}.exe", target_name)));
+        strings.push(("ProductName", self.product_name.clone()));
+        strings.push(("ProductVersion", self.version.clone()));
+
+        let string_structs = strings
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "          StringStruct({}, {}),\n",
+                    py_str(key),
+                    py_str(value)
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            "# UTF-8\n\
+             # Generated by pyside-cli, do not edit.\n\
+             VSVersionInfo(\n\
+             \x20 ffi=FixedFileInfo(\n\
+             \x20   filevers={tuple},\n\
+             \x20   prodvers={tuple},\n\
+             \x20   mask=0x3f,\n\
+             \x20   flags=0x0,\n\
+             \x20   OS=0x40004,\n\
+             \x20   fileType=0x1,\n\
+             \x20   subtype=0x0,\n\
+             \x20   date=(0, 0)\n\
+             \x20 ),\n\
+             \x20 kids=[\n\
+             \x20   StringFileInfo([\n\
+             \x20     StringTable('040904B0', [\n\
+             {string_structs}\
+             \x20     ])\n\
+             \x20   ]),\n\
+             \x20   VarFileInfo([VarStruct('Translation', [1033, 1200])])\n\
+             \x20 ]\n\
+             )\n"
+        )
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_product_metadata() {
+        let defaults = MetadataOverrides {
+            company: Some("Example Inc.".to_string()),
+            description: Some("An example".to_string()),
+            ..Default::default()
+        };
+        let target = MetadataOverrides {
+            product_name: Some("Example Viewer".to_string()),
+            ..Default::default()
+        };
+        let metadata = target
+            .or(&defaults)
+            .resolve("app", "1.2.0rc1.post3+gabc1234");
+
+        assert_eq!(metadata.product_name, "Example Viewer");
+        assert_eq!(metadata.numeric_version(), [1, 2, 0, 0]);
+        let dated = MetadataOverrides::default().resolve("app", "v2026.1019.123456");
+        assert_eq!(dated.numeric_version(), [2026, 1019, 65535, 0]);
+
+        let existing = vec!["--company-name=Other".to_string()];
+        assert_eq!(
+            metadata.nuitka_options(&existing),
+            vec![
+                "--product-name=Example Viewer".to_string(),
+                "--product-version=1.2.0.0".to_string(),
+                "--file-version=1.2.0.0".to_string(),
+                "--file-description=An example".to_string(),
+            ]
+        );
+
+        let version_file = metadata.pyinstaller_version_file("app");
+        assert!(version_file.contains("filevers=(1, 2, 0, 0)"));
+        assert!(version_file.contains("StringStruct('ProductName', 'Example Viewer')"));
+    }
+}
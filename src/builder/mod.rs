@@ -1,3 +1,4 @@
 pub mod builder;
+pub mod metadata;
 pub mod nuitka;
 pub mod pyinstaller;
@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{
-    builder::builder::Builder,
+    builder::{builder::Builder, metadata::ProductMetadata},
     cli::BuildType,
     errcode::{Errcode, GeneralErrorKind, ToolchainErrorKind},
     run_tool,
@@ -27,6 +27,7 @@ impl NuitkaBuilder {
         nuitka_exe: &Path,
         build_type: BuildType,
         extra_options: Vec<String>,
+        metadata: &ProductMetadata,
         #[cfg(target_os = "macos")] bundle_info: mac::BundleInfo,
     ) -> Result<Self, Errcode> {
         let n = thread::available_parallelism()
@@ -71,6 +72,8 @@ impl NuitkaBuilder {
         }
 
         options.extend(extra_options);
+        let metadata_options = metadata.nuitka_options(&options);
+        options.extend(metadata_options);
 
         log::debug!("Build options: {:?}", options);
 
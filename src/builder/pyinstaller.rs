@@ -1,10 +1,11 @@
 use std::{
+    fs,
     path::{Path, PathBuf},
     process::Command,
 };
 
 use crate::{
-    builder::builder::Builder,
+    builder::{builder::Builder, metadata::ProductMetadata},
     cli::BuildType,
     errcode::{Errcode, GeneralErrorKind, ToolchainErrorKind},
     run_tool,
@@ -15,6 +16,8 @@ pub struct PyInstallerBuilder {
     _target_dir: String,
     exec: PathBuf,
     options: Vec<String>,
+    /// Windows version resource written before the build.
+    version_file: Option<(PathBuf, String)>,
 }
 
 impl PyInstallerBuilder {
@@ -24,6 +27,7 @@ impl PyInstallerBuilder {
         pyinstaller_exec: &Path,
         build_type: BuildType,
         extra_options: Vec<String>,
+        metadata: &ProductMetadata,
     ) -> Result<Self, Errcode> {
         let (build_type_str, work_dir) = match build_type {
             BuildType::Onefile => ("--onefile", "build/pyinstaller_onefile_build"),
@@ -51,6 +55,16 @@ impl PyInstallerBuilder {
 
         options.extend(extra_options);
 
+        // Version resources only exist on Windows
+        let configured = options.iter().any(|opt| opt.starts_with("--version-file"));
+        let version_file = if cfg!(target_os = "windows") && !configured {
+            let path = PathBuf::from(format!("{}/{}_version_info.txt", work_dir, target_name));
+            options.push(format!("--version-file={}", path.display()));
+            Some((path, metadata.pyinstaller_version_file(target_name)))
+        } else {
+            None
+        };
+
         log::debug!("Build options: {:?}", options);
 
         Ok(PyInstallerBuilder {
@@ -58,12 +72,30 @@ impl PyInstallerBuilder {
             _target_dir: target_dir.to_string(),
             exec: pyinstaller_exec.to_path_buf(),
             options: options,
+            version_file,
         })
     }
 }
 
 impl Builder for PyInstallerBuilder {
     fn pre_build(&self) -> Result<(), Errcode> {
+        if let Some((path, content)) = &self.version_file {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
+                        path: parent.to_path_buf(),
+                        source: e,
+                    })
+                })?;
+            }
+            log::debug!("Writing version info to {}.", path.display());
+            fs::write(path, content).map_err(|e| {
+                Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+                    path: path.clone(),
+                    source: e,
+                })
+            })?;
+        }
         Ok(())
     }
 
@@ -77,7 +109,7 @@ impl Builder for PyInstallerBuilder {
         let target_spec_file = build_dir.join(format!("{}.spec", self.target_name));
         if target_spec_file.exists() {
             log::debug!("Removing old target spec file.");
-            std::fs::remove_file(&target_spec_file).map_err(|e| {
+            fs::remove_file(&target_spec_file).map_err(|e| {
                 Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
                     path: target_spec_file,
                     source: e,
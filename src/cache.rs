@@ -1,24 +1,45 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{files::Files, utils::get_file_mtime};
+use crate::utils::get_file_mtime;
 
-type CacheMap = HashMap<String, f64>;
+// Ordered so that the cache file is stable between runs
+type CacheMap = BTreeMap<String, f64>;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Cache {
     #[serde(default)]
     pub ui: CacheMap,
+    /// uic options the cached UI files were generated with
+    #[serde(default)]
+    pub ui_options: String,
+    /// Output pattern the cached UI modules were written with
+    #[serde(default)]
+    pub ui_output: String,
     #[serde(default)]
     pub i18n: CacheMap,
+    /// Assets per resource module, keyed by the generated qrc file
+    #[serde(default)]
+    pub asset_groups: BTreeMap<String, CacheMap>,
+    /// Asset settings the cached groups and qrc files were compiled with
     #[serde(default)]
-    pub assets: CacheMap,
+    pub asset_options: String,
+    /// Files referenced per hand-written qrc, keyed by the qrc file
+    #[serde(default)]
+    pub qrc_files: BTreeMap<String, CacheMap>,
 }
 
 impl Cache {
     pub fn is_empty(&self) -> bool {
-        self.ui.is_empty() && self.i18n.is_empty() && self.assets.is_empty()
+        self.ui.is_empty()
+            && self.i18n.is_empty()
+            && self.asset_groups.is_empty()
+            && self.qrc_files.is_empty()
     }
 
     fn check_outdated(file: &str, cache_map: &mut CacheMap) -> bool {
@@ -37,22 +58,111 @@ impl Cache {
         Self::check_outdated(file, &mut self.ui)
     }
 
+    /// Forgets every converted UI file when the uic options changed.
+    ///
+    /// Returns whether previously converted files were outdated by it.
+    pub fn check_ui_options(&mut self, options: &str) -> bool {
+        if self.ui_options == options {
+            return false;
+        }
+
+        let had_files = !self.ui.is_empty();
+        self.ui.clear();
+        self.ui_options = options.to_string();
+        had_files
+    }
+
+    /// Remembers the uic output pattern, returns the previous one when it changed.
+    pub fn check_ui_output(&mut self, output: &str) -> Option<String> {
+        if self.ui_output == output {
+            return None;
+        }
+
+        let previous = std::mem::replace(&mut self.ui_output, output.to_string());
+        (!previous.is_empty()).then_some(previous)
+    }
+
     pub fn check_i18n_file(&mut self, file: &str) -> bool {
         Self::check_outdated(file, &mut self.i18n)
     }
 
-    pub fn check_all_assets(&mut self, files: &Files) -> bool {
+    /// Checks a hand-written qrc together with every file it references.
+    ///
+    /// Each qrc has its own entries, files shared between qrc files outdate
+    /// all of them.
+    pub fn check_qrc_file(&mut self, qrc_file: &Path, referenced: &[PathBuf]) -> bool {
+        let cache_map = self
+            .qrc_files
+            .entry(qrc_file.to_string_lossy().to_string())
+            .or_default();
         let mut is_outdated = false;
 
-        for asset in &files.asset_list {
-            let asset_str = asset.to_string_lossy().to_string();
-            if Self::check_outdated(&asset_str, &mut self.assets) {
+        for file in std::iter::once(qrc_file).chain(referenced.iter().map(PathBuf::as_path)) {
+            let key = file.to_string_lossy().to_string();
+            if Self::check_outdated(&key, cache_map) {
                 is_outdated = true;
             }
         }
 
         is_outdated
     }
+
+    /// Outdates every asset group and hand-written qrc when the asset
+    /// settings changed.
+    ///
+    /// The groups stay known, so that removed ones are still cleaned up.
+    pub fn check_asset_options(&mut self, options: &str) -> bool {
+        if self.asset_options == options {
+            return false;
+        }
+
+        let had_files = !self.asset_groups.is_empty() || !self.qrc_files.is_empty();
+        self.asset_groups.values_mut().for_each(CacheMap::clear);
+        self.qrc_files.clear();
+        self.asset_options = options.to_string();
+        had_files
+    }
+
+    /// Checks the assets of one resource module, identified by its qrc file.
+    ///
+    /// Adding or removing an asset outdates the group as well.
+    pub fn check_asset_group(&mut self, qrc_file: &Path, asset_list: &[PathBuf]) -> bool {
+        let keys: Vec<String> = asset_list
+            .iter()
+            .map(|asset| asset.to_string_lossy().to_string())
+            .collect();
+
+        let cache_map = self
+            .asset_groups
+            .entry(qrc_file.to_string_lossy().to_string())
+            .or_default();
+        let before = cache_map.len();
+        cache_map.retain(|key, _| keys.contains(key));
+        let mut is_outdated = cache_map.len() != before;
+
+        for key in &keys {
+            if Self::check_outdated(key, cache_map) {
+                is_outdated = true;
+            }
+        }
+
+        is_outdated
+    }
+
+    /// Forgets the asset groups generated into `res_dir` that are not in
+    /// `qrc_files` any more, and returns their qrc files.
+    pub fn retain_asset_groups(&mut self, res_dir: &Path, qrc_files: &[PathBuf]) -> Vec<PathBuf> {
+        let stale: Vec<PathBuf> = self
+            .asset_groups
+            .keys()
+            .map(PathBuf::from)
+            .filter(|qrc| qrc.parent() == Some(res_dir) && !qrc_files.contains(qrc))
+            .collect();
+        for qrc in &stale {
+            self.asset_groups.remove(qrc.to_string_lossy().as_ref());
+        }
+        stale
+    }
 }
 
 pub fn load_cache() -> Cache {
@@ -91,3 +201,44 @@ pub fn save_cache(cache: &Cache) -> std::io::Result<()> {
     log::info!("Cache saved.");
     Ok(())
 }
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_qrc_files_sharing_a_file() {
+        let dir = std::env::temp_dir().join(format!("pyside-cli-qrc-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b, icon) = (dir.join("a.qrc"), dir.join("b.qrc"), dir.join("x.png"));
+        for file in [&a, &b, &icon] {
+            fs::write(file, "").unwrap();
+        }
+        let referenced = vec![icon.clone()];
+
+        let mut cache = Cache::default();
+        assert!(cache.check_qrc_file(&a, &referenced));
+        assert!(cache.check_qrc_file(&b, &referenced));
+        assert!(!cache.check_qrc_file(&a, &referenced));
+
+        // Pretend both were compiled before the shared file changed
+        for cache_map in cache.qrc_files.values_mut() {
+            cache_map.insert(icon.to_string_lossy().to_string(), 0.0);
+        }
+        assert!(cache.check_qrc_file(&a, &referenced));
+        assert!(cache.check_qrc_file(&b, &referenced));
+        assert!(!cache.check_qrc_file(&b, &referenced));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_ui_output() {
+        let mut cache = Cache::default();
+        assert_eq!(cache.check_ui_output("{stem}_ui.py"), None);
+        assert_eq!(cache.check_ui_output("{stem}_ui.py"), None);
+        assert_eq!(
+            cache.check_ui_output("ui_{stem}.py").as_deref(),
+            Some("{stem}_ui.py")
+        );
+    }
+}
@@ -6,7 +6,9 @@ use clap::{
     },
 };
 use log::LevelFilter;
+use serde::Deserialize;
 // use std::io::Write;
+use std::path::PathBuf;
 
 use crate::errcode::Errcode;
 
@@ -43,12 +45,21 @@ pub enum Command {
     /// Build the app
     Build(BuildOptions),
 
-    /// Generate translation files (.ts) for all languages
+    /// Generate translation files (.ts) for all languages, or report their status
     I18n(I18nOptions),
 
     /// Run tests
     Test(TestOptions),
 
+    /// Check project sources for problems
+    Lint(LintOptions),
+
+    /// Work with Designer forms (.ui)
+    Ui(UiOptions),
+
+    /// Format project sources
+    Fmt(FmtOptions),
+
     /// List all available build targets
     Targets,
 
@@ -77,23 +88,17 @@ pub struct BuildOptions {
     #[arg(long, conflicts_with = "onefile")]
     pub onedir: bool,
 
-    /// Final build output type
-    #[cfg(not(target_os = "macos"))]
-    #[arg(long, value_enum, default_value_t = BuildType::Onefile)]
-    pub build_type: BuildType,
-
-    /// Final build output type
-    #[cfg(target_os = "macos")]
-    #[arg(long, value_enum, default_value_t = BuildType::Bundle)]
-    pub build_type: BuildType,
+    /// Final build output type (default: `build-type` in pyproject.toml, or onefile; bundle on macOS)
+    #[arg(long, value_enum)]
+    pub build_type: Option<BuildType>,
 
     /// Build target (default: App).
     #[arg(short, long, value_name = "TARGET", default_value_t = String::from("App"))]
     pub target: String,
 
-    /// Backend to use.
-    #[arg(long, value_enum, default_value_t = Backend::Nuitka)]
-    pub backend: Backend,
+    /// Backend to use (default: `backend` in pyproject.toml, or nuitka).
+    #[arg(long, value_enum)]
+    pub backend: Option<Backend>,
 
     /// Ignore existing caches.
     #[arg(long)]
@@ -110,13 +115,14 @@ impl BuildOptions {
     /// Priority order:
     /// 1. Compatibility flags (`--onefile` / `--onedir`)
     /// 2. Explicit `--build-type` argument
-    /// 3. Fallback to default (Onedir)
+    /// 3. `build-type` from `[tool.pyside-cli]` in pyproject.toml
+    /// 4. Fallback to platform default (Bundle on macOS, Onefile elsewhere)
     ///
     /// This design allows us to:
     /// - Distinguish between "user did not specify" and "user explicitly chose"
     /// - Keep backward compatibility with legacy flags
     /// - Centralize decision logic in one place
-    pub fn resolve_build_type(&self) -> BuildType {
+    pub fn resolve_build_type(&self, configured: Option<&BuildType>) -> BuildType {
         // Compatibility flags take highest priority
         if self.onefile {
             return BuildType::Onefile;
@@ -126,12 +132,32 @@ impl BuildOptions {
             return BuildType::Onedir;
         }
 
+        if let Some(build_type) = self.build_type.as_ref().or(configured) {
+            return build_type.clone();
+        }
+
         // Default fallback when nothing is specified
-        self.build_type.clone()
+        if cfg!(target_os = "macos") {
+            BuildType::Bundle
+        } else {
+            BuildType::Onefile
+        }
+    }
+
+    /// Resolves the build backend.
+    ///
+    /// `--backend` wins over `backend` from pyproject.toml, which wins over Nuitka.
+    pub fn resolve_backend(&self, configured: Option<&Backend>) -> Backend {
+        self.backend
+            .as_ref()
+            .or(configured)
+            .cloned()
+            .unwrap_or(Backend::Nuitka)
     }
 }
 
-#[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
+#[derive(ValueEnum, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum BuildType {
     /// Build as a single executable file.
     Onefile,
@@ -159,7 +185,8 @@ pub enum BuildStage {
     All,
 }
 
-#[derive(ValueEnum, Debug, Clone)]
+#[derive(ValueEnum, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum Backend {
     /// Use Nuitka as the build backend.
     /// Ref: https://nuitka.net/
@@ -170,12 +197,147 @@ pub enum Backend {
 }
 
 #[derive(Parser, Debug, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct I18nOptions {
+    /// Without a subcommand, the translation files are generated
+    #[command(subcommand)]
+    pub command: Option<I18nCommand>,
+
     /// Target to glob i18n files for (default: App)
     #[arg(short, long, value_name = "TARGET", default_value_t = String::from("App"))]
     pub target: String,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum I18nCommand {
+    /// Show how complete the translation of each language is
+    Status(I18nStatusOptions),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct I18nStatusOptions {
+    /// Target to read translation files (.ts) of (default: App)
+    #[arg(short, long, value_name = "TARGET", default_value_t = String::from("App"))]
+    pub target: String,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value_t = StatusFormat::Table)]
+    pub format: StatusFormat,
+
+    /// Fail when a language is less complete, overrides `min-completeness`
+    /// of `[tool.pyside-cli.i18n]`
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    pub min_completeness: Option<f64>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFormat {
+    /// A table with one row per language.
+    Table,
+    /// A JSON array with one object per language.
+    Json,
+}
+
+fn parse_percent(value: &str) -> Result<f64, String> {
+    let percent: f64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is no number", value))?;
+    if !(0.0..=100.0).contains(&percent) {
+        return Err("must be between 0 and 100".to_string());
+    }
+    Ok(percent)
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LintOptions {
+    #[command(subcommand)]
+    pub command: LintCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum LintCommand {
+    /// Check Designer forms (.ui) for broken references
+    Ui(LintUiOptions),
+
+    /// Audit Designer forms (.ui) for accessibility problems
+    A11y(LintUiOptions),
+
+    /// Report unused assets and references to missing ones
+    Assets(LintUiOptions),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LintUiOptions {
+    /// Target to lint (default: App)
+    #[arg(short, long, value_name = "TARGET", default_value_t = String::from("App"))]
+    pub target: String,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+
+    /// Exit with an error on warnings too, e.g. in CI
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// One `path:line: severity: message [code]` line per problem.
+    Text,
+    /// A JSON document with all problems and their counts.
+    Json,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UiOptions {
+    #[command(subcommand)]
+    pub command: UiCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum UiCommand {
+    /// Show widget, property, layout and connection changes between two forms
+    ///
+    /// Takes an old and a new file, as passed by `git difftool -x`, or the
+    /// seven arguments of a git external diff (`diff.<driver>.command`).
+    Diff(UiDiffOptions),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UiDiffOptions {
+    /// Old and new form, `/dev/null` for a missing side
+    #[arg(value_name = "FILE", required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct FmtOptions {
+    #[command(subcommand)]
+    pub command: FmtCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum FmtCommand {
+    /// Rewrite Designer forms (.ui) into a canonical layout
+    Ui(FmtUiOptions),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct FmtUiOptions {
+    /// Target to format (default: App)
+    #[arg(short, long, value_name = "TARGET", default_value_t = String::from("App"))]
+    pub target: String,
+
+    /// Only report unformatted files and fail if there are any, e.g. in CI
+    #[arg(long)]
+    pub check: bool,
+
+    /// Format these files instead of the forms of the target
+    #[arg(value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct TestOptions {
     /// Additional arguments for the pytest
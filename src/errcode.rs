@@ -11,6 +11,8 @@ pub enum GeneralErrorKind {
     },
     #[error("Target not found: {target:?}")]
     TargetNotFound { target: String },
+    #[error("Version environment variable {name:?} is not set")]
+    VersionEnvNotSet { name: String },
     #[error("Failed to create file at {path:?}")]
     CreateFileFailed {
         path: PathBuf,
@@ -44,6 +46,42 @@ pub enum GeneralErrorKind {
     },
     #[error("File name is invalid: {name:?}")]
     FileNameInvalid { name: PathBuf },
+    #[error("File name cannot be stored in a qrc file: {name:?} ({reason})")]
+    FileNameUnrepresentable { name: PathBuf, reason: String },
+    #[error("Resource alias {alias:?} of {second:?} conflicts with {first:?}")]
+    QrcAliasConflict {
+        alias: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    #[error("Asset groups {first:?} and {second:?} would both generate the module {module:?}")]
+    AssetGroupConflict {
+        module: String,
+        first: String,
+        second: String,
+    },
+    #[error("Both {first:?} and {second:?} would generate {output:?}")]
+    OutputConflict {
+        output: PathBuf,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    #[error("Qrc file {path:?} is invalid: {msg}")]
+    QrcInvalid { path: PathBuf, msg: String },
+    #[error("Ui file {path:?} is invalid: {msg}")]
+    UiInvalid { path: PathBuf, msg: String },
+    #[error("Translation file {path:?} is invalid: {msg}")]
+    TsInvalid { path: PathBuf, msg: String },
+    #[error(
+        "Expected an old and a new file or the 7 arguments of a git external diff, got {count}"
+    )]
+    DiffArgumentsInvalid { count: usize },
+    #[error("{count} file(s) need formatting")]
+    FormatCheckFailed { count: usize },
+    #[error("Lint found {errors} error(s) and {warnings} warning(s)")]
+    LintFailed { errors: usize, warnings: usize },
+    #[error("Translations below {threshold}% complete: {languages}")]
+    TranslationsIncomplete { threshold: f64, languages: String },
     #[error("Unsupported platform: {msg:?}")]
     UnsupportedPlatform { msg: String },
 }
@@ -64,6 +102,18 @@ pub enum PyProjectErrorKind {
     FieldNotFound { field: String },
     #[error("Field is invalid")]
     FieldInvalid { field: String },
+    #[error("Invalid glob pattern in {field}")]
+    GlobInvalid {
+        field: String,
+        #[source]
+        source: globset::Error,
+    },
+    #[error("Invalid regex in {field}")]
+    RegexInvalid {
+        field: String,
+        #[source]
+        source: regex::Error,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -74,6 +124,8 @@ pub enum ToolchainErrorKind {
     UicNotFound,
     #[error("Rcc not found")]
     RccNotFound,
+    #[error("Qt rcc not found, binary resources need Qt's own rcc")]
+    QtRccNotFound,
     #[error("Git not found")]
     GitNotFound,
     #[error("Nuitka not found")]
@@ -1,66 +1,343 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
 use walkdir::WalkDir;
 
+use crate::layout::Layout;
+
+/// Patterns skipped in every target unless the user explicitly includes them.
+const DEFAULT_EXCLUDES: &[&str] = &[
+    "**/__pycache__/**",
+    "*.pyc",
+    "*.pyo",
+    "*~",
+    "*.swp",
+    "*.bak",
+    "**/.DS_Store",
+    "**/Thumbs.db",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileCategory {
+    Source,
+    Ui,
+    Asset,
+    I18n,
+    Qrc,
+}
+
+impl fmt::Display for FileCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FileCategory::Source => "source",
+            FileCategory::Ui => "ui",
+            FileCategory::Asset => "asset",
+            FileCategory::I18n => "i18n",
+            FileCategory::Qrc => "qrc",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A compiled set of glob patterns that remembers the original strings,
+/// so that we can tell which pattern matched.
+#[derive(Debug, Clone, Default)]
+pub struct PatternSet {
+    patterns: Vec<String>,
+    set: GlobSet,
+}
+
+impl PatternSet {
+    pub fn new(patterns: &[String]) -> Result<Self, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+        let set = builder.build()?;
+
+        Ok(Self {
+            patterns: patterns.to_vec(),
+            set,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns the first pattern matching `path`, if any.
+    pub fn first_match(&self, path: &Path) -> Option<&str> {
+        self.set
+            .matches(path)
+            .first()
+            .map(|&i| self.patterns[i].as_str())
+    }
+}
+
+/// Include/exclude patterns for one file category.
+///
+/// An empty include set means "everything in this category".
+#[derive(Debug, Clone, Default)]
+pub struct CategoryFilter {
+    pub include: PatternSet,
+    pub exclude: PatternSet,
+}
+
+/// Rules deciding which files of a target take part in the build.
+///
+/// Patterns are matched against paths relative to the target package,
+/// and `*` also matches `/`, so `*.psd` skips every `.psd` file.
+#[derive(Debug, Clone)]
+pub struct FileFilters {
+    /// Respect `.gitignore`, `.ignore` and git exclude files.
+    pub ignore_files: bool,
+    pub exclude: PatternSet,
+    pub source: CategoryFilter,
+    pub ui: CategoryFilter,
+    pub assets: CategoryFilter,
+    pub i18n: CategoryFilter,
+    pub qrc: CategoryFilter,
+}
+
+impl Default for FileFilters {
+    fn default() -> Self {
+        Self {
+            ignore_files: true,
+            exclude: Self::default_excludes(&[]).expect("default exclude patterns are valid"),
+            source: CategoryFilter::default(),
+            ui: CategoryFilter::default(),
+            assets: CategoryFilter::default(),
+            i18n: CategoryFilter::default(),
+            qrc: CategoryFilter::default(),
+        }
+    }
+}
+
+impl FileFilters {
+    /// Builds the global exclude set from the built-in defaults plus `extra`.
+    pub fn default_excludes(extra: &[String]) -> Result<PatternSet, globset::Error> {
+        let patterns: Vec<String> = DEFAULT_EXCLUDES
+            .iter()
+            .map(|p| p.to_string())
+            .chain(extra.iter().cloned())
+            .collect();
+        PatternSet::new(&patterns)
+    }
+
+    fn category(&self, category: FileCategory) -> &CategoryFilter {
+        match category {
+            FileCategory::Source => &self.source,
+            FileCategory::Ui => &self.ui,
+            FileCategory::Asset => &self.assets,
+            FileCategory::I18n => &self.i18n,
+            FileCategory::Qrc => &self.qrc,
+        }
+    }
+
+    /// Decides whether a file (relative to the target root) is part of `category`.
+    fn check(&self, rel_path: &Path, category: FileCategory) -> Result<(), String> {
+        let filter = self.category(category);
+
+        if filter.include.first_match(rel_path).is_some() {
+            // An explicit category include wins over the global excludes
+        } else if !filter.include.is_empty() {
+            return Err(format!("no {} include pattern matches", category));
+        } else if let Some(pattern) = self.exclude.first_match(rel_path) {
+            return Err(format!("excluded by `{}`", pattern));
+        }
+
+        if let Some(pattern) = filter.exclude.first_match(rel_path) {
+            return Err(format!("excluded by {} pattern `{}`", category, pattern));
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Files {
     pub source_list: Vec<PathBuf>,
+    /// Forms compiled by uic.
     pub ui_list: Vec<PathBuf>,
+    /// Forms embedded into the resources and loaded at runtime, see
+    /// [`Files::split_runtime_forms`].
+    pub runtime_ui_list: Vec<PathBuf>,
     pub asset_list: Vec<PathBuf>,
     pub i18n_list: Vec<PathBuf>,
+    /// Hand-written .qrc files, compiled as they are.
+    pub qrc_list: Vec<PathBuf>,
 }
 
 impl Files {
-    pub fn new(root: &Path) -> Self {
-        let assets_dir = root.join("assets");
-        let i18n_dir = root.join("i18n");
+    pub fn new(root: &Path, layout: &Layout, filters: &FileFilters) -> Self {
+        let assets_dir = layout.assets_dir(root);
+        let i18n_dir = layout.i18n_dir(root);
 
         let mut source_list = Vec::new();
         let mut ui_list = Vec::new();
         let mut asset_list = Vec::new();
         let mut i18n_list = Vec::new();
+        let mut qrc_list = Vec::new();
 
-        let exclude_dirs = [root.join("resources"), root.join("test")];
+        let exclude_dirs = [layout.resources_dir(root), layout.test_dir(root)];
+        let mut visited = HashSet::new();
 
-        for entry in WalkDir::new(root).into_iter().filter_map(Result::ok) {
-            let path = entry.path();
+        let walker = WalkBuilder::new(root)
+            .hidden(false)
+            .parents(true)
+            .require_git(false)
+            .ignore(filters.ignore_files)
+            .git_ignore(filters.ignore_files)
+            .git_global(filters.ignore_files)
+            .git_exclude(filters.ignore_files)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry({
+                let exclude_dirs = exclude_dirs.clone();
+                move |entry| {
+                    let skip = exclude_dirs.iter().any(|ex| entry.path() == ex);
+                    if skip {
+                        log::debug!("Skipped {}: reserved directory.", entry.path().display());
+                    }
+                    !skip
+                }
+            })
+            .build();
 
-            if exclude_dirs.iter().any(|ex| path.starts_with(ex)) {
-                continue;
-            }
+        for entry in walker.filter_map(Result::ok) {
+            let path = entry.path();
+            visited.insert(path.to_path_buf());
 
             if !path.is_file() {
                 continue;
             }
 
-            // assets
-            if path.starts_with(&assets_dir) {
-                asset_list.push(path.to_path_buf());
-                continue;
-            }
+            let category = if path.extension().is_some_and(|ext| ext == "qrc") {
+                FileCategory::Qrc
+            } else if path.starts_with(&assets_dir) {
+                FileCategory::Asset
+            } else if path.starts_with(&i18n_dir) {
+                FileCategory::I18n
+            } else {
+                match path.extension().and_then(|s| s.to_str()) {
+                    Some("py") => FileCategory::Source,
+                    Some("ui") => FileCategory::Ui,
+                    _ => {
+                        log::debug!("Skipped {}: unknown file type.", path.display());
+                        continue;
+                    }
+                }
+            };
 
-            // i18n
-            if path.starts_with(&i18n_dir) {
-                i18n_list.push(path.to_path_buf());
+            let rel_path = path.strip_prefix(root).unwrap_or(path);
+            if let Err(reason) = filters.check(rel_path, category) {
+                log::debug!("Skipped {}: {}.", path.display(), reason);
                 continue;
             }
 
-            // source / ui
-            match path.extension().and_then(|s| s.to_str()) {
-                Some("py") => source_list.push(path.to_path_buf()),
-                Some("ui") => ui_list.push(path.to_path_buf()),
-                _ => {}
-            }
+            log::debug!("Included {} as {} file.", path.display(), category);
+            let list = match category {
+                FileCategory::Source => &mut source_list,
+                FileCategory::Ui => &mut ui_list,
+                FileCategory::Asset => &mut asset_list,
+                FileCategory::I18n => &mut i18n_list,
+                FileCategory::Qrc => &mut qrc_list,
+            };
+            list.push(path.to_path_buf());
+        }
+
+        // Walk order depends on the filesystem, keep generated output reproducible
+        source_list.sort();
+        ui_list.sort();
+        asset_list.sort();
+        i18n_list.sort();
+        qrc_list.sort();
+
+        if filters.ignore_files && log::log_enabled!(log::Level::Debug) {
+            log_ignored_entries(root, &visited, &exclude_dirs);
         }
 
         log::debug!("Source list: {:?}", source_list);
         log::debug!("UI list: {:?}", ui_list);
         log::debug!("Asset list: {:?}", asset_list);
         log::debug!("I18n list: {:?}", i18n_list);
+        log::debug!("Qrc list: {:?}", qrc_list);
 
         Self {
-            source_list: source_list,
-            ui_list: ui_list,
-            asset_list: asset_list,
-            i18n_list: i18n_list,
+            source_list,
+            ui_list,
+            runtime_ui_list: Vec::new(),
+            asset_list,
+            i18n_list,
+            qrc_list,
+        }
+    }
+
+    /// Moves the forms `is_runtime` selects from `ui_list` to `runtime_ui_list`.
+    pub fn split_runtime_forms(&mut self, is_runtime: impl Fn(&Path) -> bool) {
+        let (runtime, compiled) = std::mem::take(&mut self.ui_list)
+            .into_iter()
+            .partition(|f| is_runtime(f));
+        self.ui_list = compiled;
+        self.runtime_ui_list = runtime;
+        log::debug!("Runtime UI list: {:?}", self.runtime_ui_list);
+    }
+}
+
+/// Lists entries hidden from the walk by ignore files.
+///
+/// Ignored directories are reported once and not descended into.
+fn log_ignored_entries(root: &Path, visited: &HashSet<PathBuf>, exclude_dirs: &[PathBuf]) {
+    let mut walker = WalkDir::new(root).sort_by_file_name().into_iter();
+
+    loop {
+        let entry = match walker.next() {
+            Some(Ok(entry)) => entry,
+            Some(Err(_)) => continue,
+            None => break,
+        };
+
+        let path = entry.path();
+        if visited.contains(path) {
+            continue;
+        }
+
+        if entry.file_type().is_dir() {
+            walker.skip_current_dir();
         }
+
+        if exclude_dirs.iter().any(|ex| path == ex) {
+            continue;
+        }
+
+        log::debug!("Skipped {}: ignored by .gitignore/.ignore.", path.display());
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_file_filters() {
+        let mut filters = FileFilters::default();
+        filters.assets.exclude = PatternSet::new(&["*.psd".to_string()]).unwrap();
+        filters.i18n.include = PatternSet::new(&["*.ts".to_string()]).unwrap();
+
+        let check = |path: &str, category| filters.check(Path::new(path), category).is_ok();
+
+        assert!(check("assets/icons/save.png", FileCategory::Asset));
+        assert!(!check("assets/icons/save.psd", FileCategory::Asset));
+        assert!(!check("assets/icons/save.png~", FileCategory::Asset));
+        assert!(!check("widgets/__pycache__/main.py", FileCategory::Source));
+        assert!(check("i18n/zh_CN.ts", FileCategory::I18n));
+        assert!(!check("i18n/README.md", FileCategory::I18n));
     }
 }
@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Directory names inside a target package, relative to the package root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// Designer forms (.ui).
+    pub ui: PathBuf,
+    /// Files embedded into the generated qrc.
+    pub assets: PathBuf,
+    /// Translation sources (.ts).
+    pub i18n: PathBuf,
+    /// Output directory for generated Python code.
+    pub resources: PathBuf,
+    /// Tests, never scanned for sources.
+    pub test: PathBuf,
+    /// Destination of compiled translations (.qm), `<assets>/i18n` unless
+    /// set, so that they are embedded with the assets.
+    pub qm: Option<PathBuf>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            ui: "ui".into(),
+            assets: "assets".into(),
+            i18n: "i18n".into(),
+            resources: "resources".into(),
+            test: "test".into(),
+            qm: None,
+        }
+    }
+}
+
+impl Layout {
+    pub fn ui_dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.ui)
+    }
+
+    pub fn assets_dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.assets)
+    }
+
+    pub fn i18n_dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.i18n)
+    }
+
+    pub fn resources_dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.resources)
+    }
+
+    pub fn test_dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.test)
+    }
+
+    pub fn qm_dir(&self, root: &Path) -> PathBuf {
+        match &self.qm {
+            Some(qm) => root.join(qm),
+            None => self.assets_dir(root).join("i18n"),
+        }
+    }
+
+    /// Returns a copy with every directory set in `overrides` replaced.
+    pub fn merged(&self, overrides: &LayoutOverrides) -> Self {
+        let pick = |value: &Option<PathBuf>, fallback: &PathBuf| {
+            value.clone().unwrap_or_else(|| fallback.clone())
+        };
+
+        Self {
+            ui: pick(&overrides.ui, &self.ui),
+            assets: pick(&overrides.assets, &self.assets),
+            i18n: pick(&overrides.i18n, &self.i18n),
+            resources: pick(&overrides.resources, &self.resources),
+            test: pick(&overrides.test, &self.test),
+            qm: overrides.qm.clone().or_else(|| self.qm.clone()),
+        }
+    }
+}
+
+/// Partial layout as written in `[tool.pyside-cli.layout]`.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct LayoutOverrides {
+    pub ui: Option<PathBuf>,
+    pub assets: Option<PathBuf>,
+    pub i18n: Option<PathBuf>,
+    pub resources: Option<PathBuf>,
+    pub test: Option<PathBuf>,
+    pub qm: Option<PathBuf>,
+}
@@ -5,10 +5,12 @@ mod cache;
 mod cli;
 mod errcode;
 mod files;
+mod layout;
 mod pyproject;
 mod qt;
 mod toolchain;
 mod utils;
+mod version;
 
 use crate::{app::run, errcode::exit_with_error};
 
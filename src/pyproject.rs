@@ -1,9 +1,20 @@
 use std::fs::read_to_string;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
+use regex::Regex;
 use serde::Deserialize;
 
+use crate::builder::metadata::MetadataOverrides;
+use crate::cli::{Backend, BuildType};
 use crate::errcode::{Errcode, GeneralErrorKind};
+use crate::files::{CategoryFilter, FileFilters, PatternSet};
+use crate::layout::{Layout, LayoutOverrides};
+use crate::qt::assets::{AssetSplit, AssetsConfig, CompressionAlgorithm, ResourceOutput};
+use crate::qt::ui::{RuntimeForms, UiConfig, UiMode, UicImports};
+use crate::version::{VersionConfig, VersionSource};
 
 #[derive(Debug, Deserialize)]
 struct PyProject {
@@ -13,9 +24,18 @@ struct PyProject {
 
 #[derive(Debug, Deserialize)]
 struct Project {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub authors: Option<Vec<Author>>,
     pub scripts: Option<HashMap<String, String>>,
 }
 
+#[derive(Debug, Deserialize)]
+struct Author {
+    pub name: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct Tool {
     #[serde(rename = "pyside-cli")]
@@ -24,8 +44,25 @@ struct Tool {
 
 #[derive(Debug, Deserialize)]
 struct PySideCli {
+    pub backend: Option<Backend>,
+
+    #[serde(rename = "build-type")]
+    pub build_type: Option<BuildType>,
+
     pub i18n: Option<I18n>,
 
+    pub ui: Option<UiTable>,
+
+    pub layout: Option<LayoutTable>,
+
+    pub files: Option<FilesTable>,
+
+    pub assets: Option<AssetsTable>,
+
+    pub version: Option<VersionTable>,
+
+    pub metadata: Option<MetadataTable>,
+
     pub pyinstaller: Option<PyInstaller>,
 
     #[serde(flatten)]
@@ -39,13 +76,119 @@ struct PyInstaller {
 }
 
 #[derive(Debug, Deserialize)]
+struct LayoutTable {
+    #[serde(flatten)]
+    pub defaults: LayoutOverrides,
+
+    /// Per-target overrides, e.g. `[tool.pyside-cli.layout.App]`
+    #[serde(flatten)]
+    pub targets: HashMap<String, LayoutOverrides>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataTable {
+    #[serde(flatten)]
+    pub defaults: MetadataOverrides,
+
+    /// Per-target overrides, e.g. `[tool.pyside-cli.metadata.App]`
+    #[serde(flatten)]
+    pub targets: HashMap<String, MetadataOverrides>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct FilesTable {
+    pub ignore_files: Option<bool>,
+    pub exclude: Option<Vec<String>>,
+    pub source: Option<FileFilterTable>,
+    pub ui: Option<FileFilterTable>,
+    pub assets: Option<FileFilterTable>,
+    pub i18n: Option<FileFilterTable>,
+    pub qrc: Option<FileFilterTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileFilterTable {
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct AssetsTable {
+    /// Asset directory -> resource prefix, e.g. `"icons" = "/icons"`
+    pub prefixes: Option<HashMap<String, String>>,
+    pub compression: Option<CompressionAlgorithm>,
+    pub compression_level: Option<u32>,
+    pub compression_threshold: Option<u32>,
+    pub no_compress: Option<Vec<String>>,
+    pub output: Option<ResourceOutput>,
+    pub split: Option<AssetSplit>,
+    /// Group name -> asset globs, e.g. `icons = ["icons/**"]`
+    pub groups: Option<BTreeMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct VersionTable {
+    pub source: Option<VersionSource>,
+    /// Glob selecting version tags, e.g. `v*`
+    pub tag_match: Option<String>,
+    pub tag_regex: Option<String>,
+    pub strip_prefix: Option<String>,
+    pub env: Option<String>,
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UiTable {
+    pub imports: Option<UicImports>,
+    pub rc_prefix: Option<bool>,
+    pub star_imports: Option<bool>,
+    /// Generated module name, e.g. `ui_{stem}.py`
+    pub output: Option<String>,
+    pub stubs: Option<bool>,
+    /// Generate `__init__.py` re-exports and a registry of the `Ui_*` classes
+    pub index: Option<bool>,
+
+    #[serde(flatten)]
+    pub defaults: UiModeTable,
+
+    /// Per-target overrides, e.g. `[tool.pyside-cli.ui.App]`
+    #[serde(flatten)]
+    pub targets: HashMap<String, UiModeTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UiModeTable {
+    pub mode: Option<UiMode>,
+    /// Forms loaded at runtime, e.g. `["plugins/**"]`
+    pub runtime: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 struct I18n {
     pub languages: Option<Vec<String>>,
+    /// Percentage of finished messages `i18n status` requires per language
+    pub min_completeness: Option<f64>,
 }
 
 pub struct PyProjectConfig {
     pub scripts: HashMap<String, PathBuf>,
+    pub backend: Option<Backend>,
+    pub build_type: Option<BuildType>,
     pub languages: Vec<String>,
+    /// Lowest translation completeness (percent) `i18n status` accepts.
+    pub min_completeness: Option<f64>,
+    pub layouts: HashMap<String, Layout>,
+    pub file_filters: FileFilters,
+    pub ui: UiConfig,
+    pub assets: AssetsConfig,
+    pub version: VersionConfig,
+    /// Product metadata of each target, the version is resolved at build time.
+    pub metadata: HashMap<String, MetadataOverrides>,
     pub extra_nuitka_options_list: Vec<String>,
     pub extra_pyinstaller_options_list: Vec<String>,
 }
@@ -64,10 +207,22 @@ impl PyProjectConfig {
         let platform = std::env::consts::OS;
 
         let scripts = parse_scripts(&cfg)?;
+        let layouts = resolve_layouts(&cfg, &scripts);
+        let metadata = resolve_metadata(&cfg, &scripts);
+        let ui = resolve_ui_config(&cfg, &scripts)?;
 
         Ok(Self {
             scripts: scripts,
+            backend: get_backend(&cfg).cloned(),
+            build_type: get_build_type(&cfg).cloned(),
             languages: get_languages(&cfg).unwrap_or_default().to_vec(),
+            min_completeness: get_min_completeness(&cfg)?,
+            layouts,
+            file_filters: resolve_file_filters(&cfg)?,
+            ui,
+            assets: resolve_assets_config(&cfg)?,
+            version: resolve_version_config(&cfg)?,
+            metadata,
             extra_nuitka_options_list: get_extra_nuitka_options_for_platform(&cfg, platform)
                 .unwrap_or_default()
                 .to_vec(),
@@ -209,6 +364,373 @@ fn get_extra_pyinstaller_options_for_platform(
     Some(get_extra_options_for_platfrom(&platforms, platform))
 }
 
+fn resolve_layouts(
+    config: &PyProject,
+    scripts: &HashMap<String, PathBuf>,
+) -> HashMap<String, Layout> {
+    let Some(table) = get_layout_table(config) else {
+        return scripts
+            .keys()
+            .map(|name| (name.clone(), Layout::default()))
+            .collect();
+    };
+
+    for name in table.targets.keys() {
+        if !scripts.contains_key(name) {
+            log::warn!("Layout defined for unknown target `{}`, ignored.", name);
+        }
+    }
+
+    let defaults = Layout::default().merged(&table.defaults);
+
+    scripts
+        .keys()
+        .map(|name| {
+            let layout = match table.targets.get(name) {
+                Some(overrides) => defaults.merged(overrides),
+                None => defaults.clone(),
+            };
+            (name.clone(), layout)
+        })
+        .collect()
+}
+
+fn resolve_metadata(
+    config: &PyProject,
+    scripts: &HashMap<String, PathBuf>,
+) -> HashMap<String, MetadataOverrides> {
+    let project = config.project.as_ref();
+    let authors = project
+        .and_then(|p| p.authors.as_ref())
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|a| a.name.as_deref())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .filter(|names| !names.is_empty());
+    let from_project = MetadataOverrides {
+        product_name: project.and_then(|p| p.name.clone()),
+        version: None,
+        company: authors,
+        description: project.and_then(|p| p.description.clone()),
+        copyright: None,
+    };
+
+    let Some(table) = get_metadata_table(config) else {
+        return scripts
+            .keys()
+            .map(|name| (name.clone(), from_project.clone()))
+            .collect();
+    };
+
+    for name in table.targets.keys() {
+        if !scripts.contains_key(name) {
+            log::warn!("Metadata defined for unknown target `{}`, ignored.", name);
+        }
+    }
+
+    let defaults = table.defaults.or(&from_project);
+
+    scripts
+        .keys()
+        .map(|name| {
+            let metadata = match table.targets.get(name) {
+                Some(overrides) => overrides.or(&defaults),
+                None => defaults.clone(),
+            };
+            (name.clone(), metadata)
+        })
+        .collect()
+}
+
+fn compile_patterns(patterns: &Option<Vec<String>>, field: &str) -> Result<PatternSet, Errcode> {
+    PatternSet::new(patterns.as_deref().unwrap_or_default()).map_err(|e| {
+        Errcode::PyProjectConfigError(PyProjectErrorKind::GlobInvalid {
+            field: field.to_string(),
+            source: e,
+        })
+    })
+}
+
+fn compile_category_filter(
+    table: &Option<FileFilterTable>,
+    name: &str,
+) -> Result<CategoryFilter, Errcode> {
+    let Some(table) = table else {
+        return Ok(CategoryFilter::default());
+    };
+
+    Ok(CategoryFilter {
+        include: compile_patterns(&table.include, &format!("files.{}.include", name))?,
+        exclude: compile_patterns(&table.exclude, &format!("files.{}.exclude", name))?,
+    })
+}
+
+fn resolve_file_filters(config: &PyProject) -> Result<FileFilters, Errcode> {
+    let Some(table) = get_files_table(config) else {
+        return Ok(FileFilters::default());
+    };
+
+    let exclude = FileFilters::default_excludes(table.exclude.as_deref().unwrap_or_default())
+        .map_err(|e| {
+            Errcode::PyProjectConfigError(PyProjectErrorKind::GlobInvalid {
+                field: "files.exclude".to_string(),
+                source: e,
+            })
+        })?;
+
+    Ok(FileFilters {
+        ignore_files: table.ignore_files.unwrap_or(true),
+        exclude,
+        source: compile_category_filter(&table.source, "source")?,
+        ui: compile_category_filter(&table.ui, "ui")?,
+        assets: compile_category_filter(&table.assets, "assets")?,
+        i18n: compile_category_filter(&table.i18n, "i18n")?,
+        qrc: compile_category_filter(&table.qrc, "qrc")?,
+    })
+}
+
+fn resolve_ui_config(
+    config: &PyProject,
+    scripts: &HashMap<String, PathBuf>,
+) -> Result<UiConfig, Errcode> {
+    let Some(table) = get_ui_table(config) else {
+        return Ok(UiConfig::default());
+    };
+
+    if let Some(output) = &table.output
+        && !UiConfig::is_valid_output(output)
+    {
+        return Err(Errcode::PyProjectConfigError(
+            PyProjectErrorKind::FieldInvalid {
+                field: "ui.output".to_string(),
+            },
+        ));
+    }
+
+    for name in table.targets.keys() {
+        if !scripts.contains_key(name) {
+            log::warn!("UI mode defined for unknown target `{}`, ignored.", name);
+        }
+    }
+
+    let mut runtime = HashMap::new();
+    for name in scripts.keys() {
+        let overrides = table.targets.get(name);
+        let mode = overrides
+            .and_then(|t| t.mode)
+            .or(table.defaults.mode)
+            .unwrap_or_default();
+        let (patterns, field) = match overrides.filter(|t| t.runtime.is_some()) {
+            Some(overrides) => (&overrides.runtime, format!("ui.{}.runtime", name)),
+            None => (&table.defaults.runtime, "ui.runtime".to_string()),
+        };
+        let patterns = PatternSet::new(patterns.as_deref().unwrap_or_default()).map_err(|e| {
+            Errcode::PyProjectConfigError(PyProjectErrorKind::GlobInvalid { field, source: e })
+        })?;
+        runtime.insert(name.clone(), RuntimeForms { mode, patterns });
+    }
+
+    let defaults = UiConfig::default();
+    Ok(UiConfig {
+        imports: table.imports.clone().unwrap_or(defaults.imports),
+        rc_prefix: table.rc_prefix.unwrap_or(defaults.rc_prefix),
+        star_imports: table.star_imports.unwrap_or(defaults.star_imports),
+        output: table.output.clone().unwrap_or(defaults.output),
+        stubs: table.stubs.unwrap_or(defaults.stubs),
+        index: table.index.unwrap_or(defaults.index),
+        runtime,
+    })
+}
+
+fn resolve_prefixes(prefixes: &HashMap<String, String>) -> Result<Vec<(PathBuf, String)>, Errcode> {
+    let mut result = Vec::new();
+
+    for (dir, prefix) in prefixes {
+        let dir = PathBuf::from(dir);
+        let prefix = format!("/{}", prefix.trim_matches('/'));
+        let escapes_assets = dir
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)));
+        if escapes_assets || prefix.contains("//") {
+            return Err(Errcode::PyProjectConfigError(
+                PyProjectErrorKind::FieldInvalid {
+                    field: format!("assets.prefixes.{}", dir.display()),
+                },
+            ));
+        }
+        result.push((dir, prefix));
+    }
+
+    // Deepest directory first, so that the most specific mapping wins
+    result.sort_by(|(a, _), (b, _)| {
+        b.components()
+            .count()
+            .cmp(&a.components().count())
+            .then_with(|| a.cmp(b))
+    });
+
+    Ok(result)
+}
+
+fn resolve_assets_config(config: &PyProject) -> Result<AssetsConfig, Errcode> {
+    let languages = get_languages(config).unwrap_or_default().to_vec();
+    let Some(table) = get_assets_table(config) else {
+        return Ok(AssetsConfig {
+            languages,
+            ..Default::default()
+        });
+    };
+
+    if let Some(level) = table.compression_level {
+        let max_level = match table.compression {
+            Some(CompressionAlgorithm::Zstd) => 19,
+            _ => 9,
+        };
+        if !(1..=max_level).contains(&level) {
+            return Err(Errcode::PyProjectConfigError(
+                PyProjectErrorKind::FieldInvalid {
+                    field: "assets.compression-level".to_string(),
+                },
+            ));
+        }
+    }
+
+    if table.compression_threshold.is_some_and(|t| t > 100) {
+        return Err(Errcode::PyProjectConfigError(
+            PyProjectErrorKind::FieldInvalid {
+                field: "assets.compression-threshold".to_string(),
+            },
+        ));
+    }
+
+    let mut groups = Vec::new();
+    for (name, patterns) in table.groups.iter().flatten() {
+        let valid_name = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && name.starts_with(|c: char| !c.is_ascii_digit());
+        if !valid_name {
+            return Err(Errcode::PyProjectConfigError(
+                PyProjectErrorKind::FieldInvalid {
+                    field: format!("assets.groups.{}", name),
+                },
+            ));
+        }
+        let field = format!("assets.groups.{}", name);
+        groups.push((
+            name.clone(),
+            compile_patterns(&Some(patterns.clone()), &field)?,
+        ));
+    }
+
+    Ok(AssetsConfig {
+        prefixes: match &table.prefixes {
+            Some(prefixes) => resolve_prefixes(prefixes)?,
+            None => Vec::new(),
+        },
+        languages,
+        compression: table.compression.clone(),
+        compression_level: table.compression_level,
+        compression_threshold: table.compression_threshold,
+        no_compress: compile_patterns(&table.no_compress, "assets.no-compress")?,
+        output: table.output.clone().unwrap_or_default(),
+        split: table.split.clone().unwrap_or_default(),
+        groups,
+    })
+}
+
+fn resolve_version_config(config: &PyProject) -> Result<VersionConfig, Errcode> {
+    let project_version = get_project_version(config).map(str::to_string);
+    let Some(table) = get_version_table(config) else {
+        return Ok(VersionConfig {
+            project_version,
+            ..Default::default()
+        });
+    };
+
+    let source = table.source.clone().unwrap_or_default();
+    match source {
+        VersionSource::Project if project_version.is_none() => {
+            return Err(Errcode::PyProjectConfigError(
+                PyProjectErrorKind::FieldNotFound {
+                    field: "project.version".to_string(),
+                },
+            ));
+        }
+        VersionSource::Env if table.env.as_deref().is_none_or(str::is_empty) => {
+            return Err(Errcode::PyProjectConfigError(
+                PyProjectErrorKind::FieldNotFound {
+                    field: "version.env".to_string(),
+                },
+            ));
+        }
+        _ => {}
+    }
+
+    let tag_regex = match &table.tag_regex {
+        Some(pattern) => Some(Regex::new(pattern).map_err(|e| {
+            Errcode::PyProjectConfigError(PyProjectErrorKind::RegexInvalid {
+                field: "version.tag-regex".to_string(),
+                source: e,
+            })
+        })?),
+        None => None,
+    };
+
+    Ok(VersionConfig {
+        source,
+        tag_match: table.tag_match.clone(),
+        tag_regex,
+        strip_prefix: table.strip_prefix.clone(),
+        env: table.env.clone(),
+        project_version,
+        fallback: table.fallback.clone(),
+    })
+}
+
+fn get_metadata_table(config: &PyProject) -> Option<&MetadataTable> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.metadata.as_ref()
+}
+
+fn get_version_table(config: &PyProject) -> Option<&VersionTable> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.version.as_ref()
+}
+
+fn get_project_version(config: &PyProject) -> Option<&str> {
+    config.project.as_ref()?.version.as_deref()
+}
+
+fn get_ui_table(config: &PyProject) -> Option<&UiTable> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.ui.as_ref()
+}
+
+fn get_assets_table(config: &PyProject) -> Option<&AssetsTable> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.assets.as_ref()
+}
+
+fn get_files_table(config: &PyProject) -> Option<&FilesTable> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.files.as_ref()
+}
+
+fn get_layout_table(config: &PyProject) -> Option<&LayoutTable> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.layout.as_ref()
+}
+
+fn get_backend(config: &PyProject) -> Option<&Backend> {
+    config.tool.as_ref()?.pyside_cli.as_ref()?.backend.as_ref()
+}
+
+fn get_build_type(config: &PyProject) -> Option<&BuildType> {
+    config
+        .tool
+        .as_ref()?
+        .pyside_cli
+        .as_ref()?
+        .build_type
+        .as_ref()
+}
+
 fn get_languages<'a>(config: &'a PyProject) -> Option<&'a [String]> {
     config
         .tool
@@ -221,6 +743,24 @@ fn get_languages<'a>(config: &'a PyProject) -> Option<&'a [String]> {
         .as_deref()
 }
 
+fn get_min_completeness(config: &PyProject) -> Result<Option<f64>, Errcode> {
+    let min_completeness = config
+        .tool
+        .as_ref()
+        .and_then(|t| t.pyside_cli.as_ref())
+        .and_then(|c| c.i18n.as_ref())
+        .and_then(|i| i.min_completeness);
+
+    if min_completeness.is_some_and(|p| !(0.0..=100.0).contains(&p)) {
+        return Err(Errcode::PyProjectConfigError(
+            PyProjectErrorKind::FieldInvalid {
+                field: "i18n.min-completeness".to_string(),
+            },
+        ));
+    }
+    Ok(min_completeness)
+}
+
 fn get_scripts<'a>(config: &'a PyProject) -> Option<&'a HashMap<String, String>> {
     config.project.as_ref()?.scripts.as_ref()
 }
@@ -228,17 +768,348 @@ fn get_scripts<'a>(config: &'a PyProject) -> Option<&'a HashMap<String, String>>
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+    #[allow(unused_imports)]
+    use std::path::Path;
 
     #[test]
     fn test_parsing_pyproject_i18n() {
         let pyproject = r#"
             [tool.pyside-cli.i18n]
             languages = ["en_US", "zh_CN"]
+            min-completeness = 95
         "#;
 
         let project: PyProject = toml::from_str(pyproject).unwrap();
         let languages = get_languages(&project).unwrap_or_default();
         assert_eq!(languages, &["en_US", "zh_CN"]);
+        assert_eq!(get_min_completeness(&project).unwrap(), Some(95.0));
+
+        let invalid = r#"
+            [tool.pyside-cli.i18n]
+            min-completeness = 120
+        "#;
+        let project: PyProject = toml::from_str(invalid).unwrap();
+        assert!(get_min_completeness(&project).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_build_defaults() {
+        let pyproject = r#"
+            [tool.pyside-cli]
+            backend = "pyinstaller"
+            build-type = "onedir"
+            standalone = true
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        assert_eq!(get_backend(&project), Some(&Backend::Pyinstaller));
+        assert_eq!(get_build_type(&project), Some(&BuildType::Onedir));
+
+        // Known keys must not leak into the extra Nuitka options
+        let options = get_extra_nuitka_options_for_platform(&project, "linux").unwrap_or_default();
+        assert_eq!(options, vec!["--standalone".to_string()]);
+    }
+
+    #[test]
+    fn test_parsing_pyproject_layout() {
+        let pyproject = r#"
+            [tool.pyside-cli.layout]
+            ui = "forms"
+            resources = "_generated"
+
+            [tool.pyside-cli.layout.Legacy]
+            qm = "translations"
+
+            [tool.pyside-cli.layout.Res]
+            assets = "res"
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let scripts = HashMap::from([
+            ("App".to_string(), PathBuf::from("app")),
+            ("Legacy".to_string(), PathBuf::from("legacy")),
+            ("Res".to_string(), PathBuf::from("res_app")),
+        ]);
+        let layouts = resolve_layouts(&project, &scripts);
+
+        let app = &layouts["App"];
+        assert_eq!(app.ui, PathBuf::from("forms"));
+        assert_eq!(app.resources, PathBuf::from("_generated"));
+        assert_eq!(app.assets, PathBuf::from("assets"));
+        assert_eq!(
+            app.qm_dir(Path::new("app")),
+            PathBuf::from("app/assets/i18n")
+        );
+
+        let legacy = &layouts["Legacy"];
+        assert_eq!(legacy.ui, PathBuf::from("forms"));
+        assert_eq!(
+            legacy.qm_dir(Path::new("legacy")),
+            PathBuf::from("legacy/translations")
+        );
+
+        // The .qm files follow the assets unless placed explicitly
+        let res = &layouts["Res"];
+        assert_eq!(
+            res.qm_dir(Path::new("res_app")),
+            PathBuf::from("res_app/res/i18n")
+        );
+    }
+
+    #[test]
+    fn test_parsing_pyproject_files() {
+        let pyproject = r#"
+            [tool.pyside-cli.files]
+            ignore-files = false
+            exclude = ["**/.venv/**"]
+
+            [tool.pyside-cli.files.assets]
+            exclude = ["*.psd"]
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let filters = resolve_file_filters(&project).unwrap();
+        assert!(!filters.ignore_files);
+        assert!(
+            filters
+                .exclude
+                .first_match(Path::new("app/.venv/lib/x.py"))
+                .is_some()
+        );
+        assert!(
+            filters
+                .exclude
+                .first_match(Path::new("__pycache__/x.pyc"))
+                .is_some()
+        );
+        assert_eq!(
+            filters
+                .assets
+                .exclude
+                .first_match(Path::new("assets/logo.psd")),
+            Some("*.psd")
+        );
+
+        let invalid = r#"
+            [tool.pyside-cli.files.ui]
+            include = ["forms/[a"]
+        "#;
+        let project: PyProject = toml::from_str(invalid).unwrap();
+        assert!(resolve_file_filters(&project).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_ui() {
+        let pyproject = r#"
+            [tool.pyside-cli.ui]
+            imports = "absolute"
+            star-imports = true
+            output = "ui_{stem}.py"
+            index = true
+        "#;
+
+        let scripts = HashMap::from([
+            ("App".to_string(), PathBuf::from("app")),
+            ("Plugin".to_string(), PathBuf::from("plugin")),
+        ]);
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let config = resolve_ui_config(&project, &scripts).unwrap();
+        assert_eq!(config.imports, UicImports::Absolute);
+        assert!(config.star_imports);
+        assert!(!config.rc_prefix);
+        assert_eq!(config.output, "ui_{stem}.py");
+        assert!(config.index);
+        assert!(!config.runtime["App"].contains(std::path::Path::new("plugins/chart.ui")));
+
+        let runtime = r#"
+            [tool.pyside-cli.ui]
+            runtime = ["plugins/**"]
+
+            [tool.pyside-cli.ui.Plugin]
+            mode = "runtime"
+        "#;
+        let project: PyProject = toml::from_str(runtime).unwrap();
+        let config = resolve_ui_config(&project, &scripts).unwrap();
+        assert!(config.runtime["App"].contains(std::path::Path::new("plugins/chart.ui")));
+        assert!(!config.runtime["App"].contains(std::path::Path::new("main.ui")));
+        assert_eq!(config.runtime["Plugin"].mode, UiMode::Runtime);
+
+        let invalid = r#"
+            [tool.pyside-cli.ui]
+            output = "{stem}.txt"
+        "#;
+        let project: PyProject = toml::from_str(invalid).unwrap();
+        assert!(resolve_ui_config(&project, &scripts).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_assets_prefixes() {
+        let pyproject = r#"
+            [tool.pyside-cli.assets.prefixes]
+            "icons" = "/icons"
+            "themes" = "themes/"
+            "themes/dark" = "/theme"
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let config = resolve_assets_config(&project).unwrap();
+        assert_eq!(
+            config.prefixes,
+            vec![
+                (PathBuf::from("themes/dark"), "/theme".to_string()),
+                (PathBuf::from("icons"), "/icons".to_string()),
+                (PathBuf::from("themes"), "/themes".to_string()),
+            ]
+        );
+
+        let invalid = r#"
+            [tool.pyside-cli.assets.prefixes]
+            "../outside" = "/x"
+        "#;
+        let project: PyProject = toml::from_str(invalid).unwrap();
+        assert!(resolve_assets_config(&project).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_assets_compression() {
+        let pyproject = r#"
+            [tool.pyside-cli.assets]
+            compression = "zstd"
+            compression-level = 19
+            compression-threshold = 30
+            no-compress = ["*.png", "*.mp3"]
+            output = "both"
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let config = resolve_assets_config(&project).unwrap();
+        assert_eq!(config.output, ResourceOutput::Both);
+        assert_eq!(
+            config.rcc_args(),
+            vec![
+                "--compress-algo",
+                "zstd",
+                "--compress",
+                "19",
+                "--threshold",
+                "30"
+            ]
+        );
+        assert!(
+            config
+                .no_compress
+                .first_match(Path::new("icons/save.png"))
+                .is_some()
+        );
+
+        let invalid = r#"
+            [tool.pyside-cli.assets]
+            compression = "zlib"
+            compression-level = 19
+        "#;
+        let project: PyProject = toml::from_str(invalid).unwrap();
+        assert!(resolve_assets_config(&project).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_assets_groups() {
+        let pyproject = r#"
+            [tool.pyside-cli.assets]
+            split = "directory"
+
+            [tool.pyside-cli.assets.groups]
+            media = ["sounds/**", "videos/**"]
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let config = resolve_assets_config(&project).unwrap();
+        assert_eq!(config.split, AssetSplit::Directory);
+        assert_eq!(config.groups.len(), 1);
+        assert_eq!(config.groups[0].0, "media");
+
+        let invalid = r#"
+            [tool.pyside-cli.assets.groups]
+            "2d-sprites" = ["sprites/**"]
+        "#;
+        let project: PyProject = toml::from_str(invalid).unwrap();
+        assert!(resolve_assets_config(&project).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_version() {
+        let pyproject = r#"
+            [project]
+            version = "1.4.0"
+
+            [tool.pyside-cli.version]
+            tag-match = "v*"
+            tag-regex = '^v\d+\.\d+\.\d+$'
+            strip-prefix = "v"
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let config = resolve_version_config(&project).unwrap();
+        assert_eq!(config.source, VersionSource::Git);
+        assert_eq!(config.tag_match.as_deref(), Some("v*"));
+        assert!(config.tag_regex.is_some());
+        assert_eq!(config.project_version.as_deref(), Some("1.4.0"));
+
+        // Known keys must not leak into the extra Nuitka options
+        let options = get_extra_nuitka_options_for_platform(&project, "linux").unwrap_or_default();
+        assert!(options.is_empty());
+
+        let missing_env = r#"
+            [tool.pyside-cli.version]
+            source = "env"
+        "#;
+        let project: PyProject = toml::from_str(missing_env).unwrap();
+        assert!(resolve_version_config(&project).is_err());
+
+        let missing_project_version = r#"
+            [tool.pyside-cli.version]
+            source = "project"
+        "#;
+        let project: PyProject = toml::from_str(missing_project_version).unwrap();
+        assert!(resolve_version_config(&project).is_err());
+    }
+
+    #[test]
+    fn test_parsing_pyproject_metadata() {
+        let pyproject = r#"
+            [project]
+            name = "example"
+            description = "An example app"
+            authors = [{ name = "Ada" }, { name = "Bob", email = "bob@example.com" }]
+
+            [tool.pyside-cli.metadata]
+            copyright = "(C) Example"
+
+            [tool.pyside-cli.metadata.Tool]
+            product-name = "Example Tool"
+            version = "2.0"
+        "#;
+
+        let project: PyProject = toml::from_str(pyproject).unwrap();
+        let scripts = HashMap::from([
+            ("App".to_string(), PathBuf::from("app")),
+            ("Tool".to_string(), PathBuf::from("tool")),
+        ]);
+        let metadata = resolve_metadata(&project, &scripts);
+
+        let app = &metadata["App"];
+        assert_eq!(app.product_name.as_deref(), Some("example"));
+        assert_eq!(app.company.as_deref(), Some("Ada, Bob"));
+        assert_eq!(app.copyright.as_deref(), Some("(C) Example"));
+        assert_eq!(app.version, None);
+
+        let tool = &metadata["Tool"];
+        assert_eq!(tool.product_name.as_deref(), Some("Example Tool"));
+        assert_eq!(tool.description.as_deref(), Some("An example app"));
+        assert_eq!(tool.version.as_deref(), Some("2.0"));
+
+        // Known keys must not leak into the extra Nuitka options
+        let options = get_extra_nuitka_options_for_platform(&project, "linux").unwrap_or_default();
+        assert!(options.is_empty());
     }
 
     #[test]
@@ -0,0 +1,420 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::qt::{
+    lint::{Diagnostic, Severity, mnemonic},
+    ui_form::{UiForm, UiObject, UiObjectKind},
+};
+
+/// Buttons are named after their text.
+const BUTTON_CLASSES: &[&str] = &[
+    "QPushButton",
+    "QToolButton",
+    "QCheckBox",
+    "QRadioButton",
+    "QCommandLinkButton",
+];
+
+/// Input widgets are named after their buddy label.
+const INPUT_CLASSES: &[&str] = &[
+    "QLineEdit",
+    "QTextEdit",
+    "QPlainTextEdit",
+    "QTextBrowser",
+    "QSpinBox",
+    "QDoubleSpinBox",
+    "QComboBox",
+    "QFontComboBox",
+    "QSlider",
+    "QDial",
+    "QScrollBar",
+    "QDateEdit",
+    "QTimeEdit",
+    "QDateTimeEdit",
+    "QKeySequenceEdit",
+    "QListWidget",
+    "QListView",
+    "QTreeWidget",
+    "QTreeView",
+    "QTableWidget",
+    "QTableView",
+    "QColumnView",
+    "QCalendarWidget",
+];
+
+/// Containers showing one page at a time, shortcuts on hidden pages are inactive.
+const PAGED_CLASSES: &[&str] = &["QTabWidget", "QStackedWidget", "QToolBox"];
+
+/// A keyboard accelerator, e.g. `n` of `&Name`.
+struct Accelerator<'a> {
+    key: char,
+    object: &'a UiObject,
+    text: &'a str,
+    /// Pages the object is placed on, outermost first.
+    pages: Vec<&'a str>,
+}
+
+/// Checks a parsed form for accessibility problems, all reported as warnings.
+pub fn audit_ui_form(path: &Path, form: &UiForm) -> Vec<Diagnostic> {
+    let audit = Audit::new(path, form);
+    let mut diagnostics = Vec::new();
+    audit.check_names(&mut diagnostics);
+    audit.check_labels(&mut diagnostics);
+    audit.check_accelerators(&mut diagnostics);
+
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+struct Audit<'a> {
+    path: PathBuf,
+    form: &'a UiForm,
+    names: HashMap<&'a str, &'a UiObject>,
+}
+
+impl<'a> Audit<'a> {
+    fn new(path: &Path, form: &'a UiForm) -> Self {
+        let names = std::iter::once(&form.root)
+            .chain(&form.objects)
+            .map(|o| (o.name.as_str(), o))
+            .collect();
+        Self {
+            path: path.to_path_buf(),
+            form,
+            names,
+        }
+    }
+
+    fn diagnostic(&self, line: u32, code: &'static str, message: String) -> Diagnostic {
+        Diagnostic {
+            path: self.path.clone(),
+            line,
+            severity: Severity::Warning,
+            code,
+            message,
+        }
+    }
+
+    fn is_class(&self, object: &UiObject, classes: &[&str]) -> bool {
+        classes.contains(&self.form.base_class(&object.class))
+    }
+
+    fn widgets(&self) -> impl Iterator<Item = &'a UiObject> {
+        self.form
+            .objects
+            .iter()
+            .filter(|o| o.kind == UiObjectKind::Widget)
+    }
+
+    /// Label sharing a form or grid layout row with `object`, left of it.
+    fn row_label(&self, object: &UiObject) -> Option<&'a UiObject> {
+        let (row, column) = object.cell?;
+        let parent = object.parent.as_deref()?;
+        self.widgets().find(|o| {
+            o.class == "QLabel"
+                && o.parent.as_deref() == Some(parent)
+                && o.cell.is_some_and(|(r, c)| r == row && c + 1 == column)
+        })
+    }
+
+    /// Input widget right of `label` in a form or grid layout row.
+    fn row_field(&self, label: &UiObject) -> Option<&'a UiObject> {
+        let (row, column) = label.cell?;
+        let parent = label.parent.as_deref()?;
+        self.widgets().find(|o| {
+            o.parent.as_deref() == Some(parent)
+                && o.cell == Some((row, column + 1))
+                && self.is_class(o, INPUT_CLASSES)
+        })
+    }
+
+    fn check_names(&self, diagnostics: &mut Vec<Diagnostic>) {
+        for widget in self.widgets() {
+            let is_button = self.is_class(widget, BUTTON_CLASSES);
+            if !is_button && !self.is_class(widget, INPUT_CLASSES) {
+                continue;
+            }
+            if widget.text("accessibleName").is_some() {
+                continue;
+            }
+
+            if is_button {
+                if widget.text("text").is_some() {
+                    continue;
+                }
+                if widget.property("icon").is_some() {
+                    diagnostics.push(self.diagnostic(
+                        widget.line,
+                        "icon-only-button",
+                        format!(
+                            "button `{}` only shows an icon, set a text or accessibleName",
+                            widget.name
+                        ),
+                    ));
+                    continue;
+                }
+            } else {
+                let has_buddy = self.widgets().any(|o| {
+                    o.class == "QLabel"
+                        && o.property("buddy").is_some_and(|b| b.value == widget.name)
+                });
+                if has_buddy || self.row_label(widget).is_some() {
+                    continue;
+                }
+            }
+
+            if widget.text("toolTip").is_none() {
+                diagnostics.push(self.diagnostic(
+                    widget.line,
+                    "missing-accessible-name",
+                    format!(
+                        "{} `{}` has no accessibleName, toolTip or label",
+                        widget.class, widget.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Labels of input fields need a buddy so the shortcut and screen
+    /// readers reach the field.
+    fn check_labels(&self, diagnostics: &mut Vec<Diagnostic>) {
+        for label in self.widgets().filter(|o| o.class == "QLabel") {
+            if label.property("buddy").is_some() {
+                continue;
+            }
+            let Some(text) = label.text("text") else {
+                continue;
+            };
+
+            let field = self.row_field(label);
+            if field.is_some() || mnemonic(text).is_some() || text.trim_end().ends_with(':') {
+                let message = match field {
+                    Some(field) => format!(
+                        "label `{}` describes `{}` but has no buddy",
+                        label.name, field.name
+                    ),
+                    None => format!("label `{}` (`{}`) has no buddy", label.name, text),
+                };
+                diagnostics.push(self.diagnostic(label.line, "label-without-buddy", message));
+            }
+        }
+    }
+
+    fn check_accelerators(&self, diagnostics: &mut Vec<Diagnostic>) {
+        // Widgets, tab titles and menu bar entries share the window
+        let mut window = Vec::new();
+        for object in self.widgets() {
+            if self.in_menu(object) {
+                continue;
+            }
+            let text = match object.class.as_str() {
+                "QMenu" | "QMenuBar" => None,
+                // Labels only forward their shortcut to a buddy
+                "QLabel" => object.property("buddy").and_then(|_| object.text("text")),
+                _ => object.text("text").or_else(|| object.text("title")),
+            };
+            let pages = self.pages(object);
+            if let Some(text) = text {
+                window.extend(self.accelerator(object, text, pages.clone()));
+            }
+
+            // The page title is shown by the container, outside the page
+            if let Some(text) = object
+                .attribute("title")
+                .or_else(|| object.attribute("label"))
+                && !text.value.is_empty()
+            {
+                window.extend(self.accelerator(
+                    object,
+                    &text.value,
+                    pages[..pages.len().saturating_sub(1)].to_vec(),
+                ));
+            }
+        }
+        for menu_bar in self.widgets().filter(|o| o.class == "QMenuBar") {
+            window.extend(
+                self.menu_entries(menu_bar)
+                    .filter_map(|(object, text)| self.accelerator(object, text, Vec::new())),
+            );
+        }
+        self.report_conflicts(&window, "the window", diagnostics);
+
+        for menu in self.widgets().filter(|o| o.class == "QMenu") {
+            let entries: Vec<_> = self
+                .menu_entries(menu)
+                .filter_map(|(object, text)| self.accelerator(object, text, Vec::new()))
+                .collect();
+            self.report_conflicts(&entries, &format!("menu `{}`", menu.name), diagnostics);
+        }
+    }
+
+    fn accelerator(
+        &self,
+        object: &'a UiObject,
+        text: &'a str,
+        pages: Vec<&'a str>,
+    ) -> Option<Accelerator<'a>> {
+        mnemonic(text).map(|key| Accelerator {
+            key,
+            object,
+            text,
+            pages,
+        })
+    }
+
+    /// Actions and sub menus of a menu or menu bar with their texts.
+    fn menu_entries(&self, menu: &'a UiObject) -> impl Iterator<Item = (&'a UiObject, &'a str)> {
+        menu.actions.iter().filter_map(|name| {
+            let object = self.names.get(name.as_str())?;
+            let text = match object.class.as_str() {
+                "QMenu" => object.text("title"),
+                _ => object.text("text"),
+            }?;
+            Some((*object, text))
+        })
+    }
+
+    fn ancestors(&self, object: &UiObject) -> impl Iterator<Item = &'a UiObject> {
+        let mut parent = object.parent.clone();
+        std::iter::from_fn(move || {
+            let object = self.names.get(parent.as_deref()?)?;
+            parent = object.parent.clone();
+            Some(*object)
+        })
+        .take(self.names.len())
+    }
+
+    fn in_menu(&self, object: &UiObject) -> bool {
+        self.ancestors(object).any(|o| o.class == "QMenu")
+    }
+
+    /// Pages of tab, stacked and tool box widgets `object` is placed on.
+    fn pages(&self, object: &'a UiObject) -> Vec<&'a str> {
+        let mut pages = Vec::new();
+        let mut child = object;
+        for ancestor in self.ancestors(object) {
+            if self.is_class(ancestor, PAGED_CLASSES) {
+                pages.push(child.name.as_str());
+            }
+            child = ancestor;
+        }
+        pages.reverse();
+        pages
+    }
+
+    fn report_conflicts(
+        &self,
+        accelerators: &[Accelerator],
+        scope: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for (i, accelerator) in accelerators.iter().enumerate() {
+            // Shortcuts on different pages are never visible together
+            let first = accelerators[..i].iter().find(|other| {
+                other.key == accelerator.key
+                    && (other.pages.starts_with(&accelerator.pages)
+                        || accelerator.pages.starts_with(&other.pages))
+            });
+            if let Some(first) = first {
+                diagnostics.push(self.diagnostic(
+                    accelerator.object.line,
+                    "conflicting-accelerator",
+                    format!(
+                        "shortcut Alt+{} of `{}` ({}) conflicts with `{}` ({}) in {}",
+                        accelerator.key.to_ascii_uppercase(),
+                        accelerator.object.name,
+                        accelerator.text,
+                        first.object.name,
+                        first.text,
+                        scope
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_audit_ui_form() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>MainWindow</class>
+ <widget class="QMainWindow" name="MainWindow">
+  <widget class="QWidget" name="central">
+   <layout class="QFormLayout" name="form">
+    <item row="0" column="0">
+     <widget class="QLabel" name="nameLabel">
+      <property name="text"><string>&amp;Name:</string></property>
+     </widget>
+    </item>
+    <item row="0" column="1">
+     <widget class="QLineEdit" name="nameEdit"/>
+    </item>
+    <item row="1" column="0">
+     <widget class="QLabel" name="mailLabel">
+      <property name="text"><string>&amp;Mail:</string></property>
+      <property name="buddy"><cstring>mailEdit</cstring></property>
+     </widget>
+    </item>
+    <item row="1" column="1">
+     <widget class="QLineEdit" name="mailEdit"/>
+    </item>
+    <item row="2" column="1">
+     <widget class="QSpinBox" name="ageSpin"/>
+    </item>
+    <item row="3" column="1">
+     <widget class="QPushButton" name="saveButton">
+      <property name="icon"><iconset><normaloff>:/icons/save.png</normaloff></iconset></property>
+     </widget>
+    </item>
+    <item row="4" column="1">
+     <widget class="QPushButton" name="moreButton">
+      <property name="text"><string>&amp;More</string></property>
+     </widget>
+    </item>
+   </layout>
+  </widget>
+  <widget class="QMenuBar" name="menubar">
+   <widget class="QMenu" name="menuFile">
+    <property name="title"><string>&amp;File</string></property>
+    <addaction name="actionOpen"/>
+    <addaction name="separator"/>
+    <addaction name="actionOptions"/>
+   </widget>
+   <addaction name="menuFile"/>
+  </widget>
+  <action name="actionOpen">
+   <property name="text"><string>&amp;Open</string></property>
+  </action>
+  <action name="actionOptions">
+   <property name="text"><string>&amp;Options</string></property>
+  </action>
+ </widget>
+</ui>
+"#;
+        let form = UiForm::parse(xml).unwrap();
+        let diagnostics = audit_ui_form(Path::new("main.ui"), &form);
+        let codes: Vec<(u32, &str)> = diagnostics.iter().map(|d| (d.line, d.code)).collect();
+        assert_eq!(
+            codes,
+            [
+                (8, "label-without-buddy"),
+                (25, "missing-accessible-name"),
+                (28, "icon-only-button"),
+                (33, "conflicting-accelerator"),
+                (51, "conflicting-accelerator"),
+            ]
+        );
+        assert_eq!(
+            diagnostics[3].message,
+            "shortcut Alt+M of `moreButton` (&More) conflicts with `mailLabel` (&Mail:) in the window"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+
+use crate::{qt::qrc::clean_resource_path, utils::py_str};
+
+/// Module generated next to the resource modules with a constant per asset.
+pub const ASSET_INDEX_MODULE: &str = "assets_index.py";
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// One directory of the resource system, generated as a nested class.
+#[derive(Debug, Default)]
+struct Namespace {
+    /// Resource path of the directory, e.g. `/icons/`
+    directory: String,
+    classes: BTreeMap<String, Namespace>,
+    /// Constant name -> resource path
+    constants: BTreeMap<String, String>,
+}
+
+impl Namespace {
+    fn insert(&mut self, resource: &str) {
+        let resource = clean_resource_path(resource);
+        let mut components: Vec<&str> = resource.split('/').filter(|c| !c.is_empty()).collect();
+        let Some(file_name) = components.pop() else {
+            return;
+        };
+
+        let mut namespace = self;
+        let mut directory = String::from("/");
+        for dir in components {
+            let class = class_name(dir);
+            if namespace.constants.contains_key(&class) {
+                log::warn!(
+                    "{} clashes with a file of its directory, skipped.",
+                    resource
+                );
+                return;
+            }
+            directory.push_str(dir);
+            directory.push('/');
+            namespace = namespace.classes.entry(class).or_insert_with(|| Namespace {
+                directory: directory.clone(),
+                ..Default::default()
+            });
+        }
+
+        let constant = constant_name(file_name);
+        if namespace.classes.contains_key(&constant) {
+            log::warn!("{} clashes with a directory, skipped.", resource);
+            return;
+        }
+        if let Some(first) = namespace.constants.get(&constant) {
+            log::warn!(
+                "{} and :{} are both named {}, only the first is indexed.",
+                first,
+                resource,
+                constant
+            );
+            return;
+        }
+        namespace
+            .constants
+            .insert(constant, format!(":{}", resource));
+    }
+
+    fn write(&self, out: &mut String, depth: usize, stub: bool) {
+        let indent = "    ".repeat(depth);
+        if self.classes.is_empty() && self.constants.is_empty() {
+            out.push_str(&format!("{}pass\n", indent));
+            return;
+        }
+
+        for (name, path) in &self.constants {
+            let annotation = if stub { ": Final" } else { "" };
+            out.push_str(&format!(
+                "{}{}{} = {}\n",
+                indent,
+                name,
+                annotation,
+                py_str(path)
+            ));
+        }
+        for (i, (name, namespace)) in self.classes.iter().enumerate() {
+            if i > 0 || !self.constants.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("{}class {}:\n", indent, name));
+            namespace.write(out, depth + 1, stub);
+        }
+    }
+}
+
+/// The `Assets` class of the asset index, to find the resources sources
+/// refer to through it.
+pub struct AssetIndex {
+    root: Namespace,
+}
+
+impl AssetIndex {
+    pub fn new<'a>(resources: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut root = Namespace {
+            directory: "/".to_string(),
+            ..Default::default()
+        };
+        for resource in resources {
+            root.insert(resource);
+        }
+        Self { root }
+    }
+
+    /// Resolves an attribute chain below `Assets`, e.g. `["icons", "SAVE_PNG"]`,
+    /// to `(resource path, is a directory)`.
+    ///
+    /// A directory is reported with its path, e.g. `/icons/`, trailing
+    /// attributes of a constant (like `.upper`) are ignored.
+    pub fn resolve(&self, attributes: &[&str]) -> Option<(String, bool)> {
+        let mut namespace = &self.root;
+        for attribute in attributes {
+            if let Some(path) = namespace.constants.get(*attribute) {
+                return Some((path.trim_start_matches(':').to_string(), false));
+            }
+            namespace = namespace.classes.get(*attribute)?;
+        }
+        Some((namespace.directory.clone(), true))
+    }
+}
+
+/// `my-icons` becomes `my_icons`, keywords get a trailing `_`.
+fn class_name(dir: &str) -> String {
+    let mut name = identifier(dir);
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+/// `save.png` becomes `SAVE_PNG`.
+fn constant_name(file_name: &str) -> String {
+    identifier(file_name).to_ascii_uppercase()
+}
+
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+/// Source of the asset index and its stub, mirroring the resource paths
+/// (e.g. `/icons/save.png`) as `Assets.icons.SAVE_PNG`.
+pub fn asset_index_modules<'a>(
+    resources: impl IntoIterator<Item = &'a String>,
+) -> (String, String) {
+    let root = AssetIndex::new(resources).root;
+
+    let mut module = String::from(
+        "# Generated by pyside-cli, do not edit.\n\
+         \"\"\"Resource paths of the assets, e.g. `Assets.icons.SAVE_PNG`.\"\"\"\n\
+         \n\
+         \n\
+         class Assets:\n",
+    );
+    root.write(&mut module, 1, false);
+
+    let mut stub = String::from(
+        "# Generated by pyside-cli, do not edit.\n\
+         from typing import Final\n\
+         \n\
+         class Assets:\n",
+    );
+    root.write(&mut stub, 1, true);
+
+    (module, stub)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_asset_index_modules() {
+        let resources = [
+            "/app.ico".to_string(),
+            "/icons/save.png".to_string(),
+            "/icons/save-png".to_string(),
+            "/icons/16x16/open.png".to_string(),
+            "/import/data.json".to_string(),
+            "/themes/../import/./config.json".to_string(),
+        ];
+        let (module, stub) = asset_index_modules(&resources);
+        assert!(module.ends_with(
+            "class Assets:\n\
+             \x20   APP_ICO = ':/app.ico'\n\
+             \n\
+             \x20   class icons:\n\
+             \x20       SAVE_PNG = ':/icons/save.png'\n\
+             \n\
+             \x20       class _16x16:\n\
+             \x20           OPEN_PNG = ':/icons/16x16/open.png'\n\
+             \n\
+             \x20   class import_:\n\
+             \x20       CONFIG_JSON = ':/import/config.json'\n\
+             \x20       DATA_JSON = ':/import/data.json'\n"
+        ));
+        assert!(stub.contains("        SAVE_PNG: Final = ':/icons/save.png'\n"));
+    }
+}
@@ -0,0 +1,273 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+
+use crate::{
+    qt::{
+        asset_index::AssetIndex,
+        lint::{Diagnostic, Severity},
+        qrc::{QrcEntry, clean_resource_path},
+    },
+    utils::format_size,
+};
+
+/// `:/icons/save.png`, `qrc:/icons/save.png` or `qrc:///icons/save.png`, the
+/// scheme is captured to tell them apart from URLs like `https://`.
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([A-Za-z][\w+.-]*)?:(/[^\s"'`()<>;,\\]*)"#).unwrap());
+
+/// `Assets.icons.SAVE_PNG` of the generated asset index.
+static INDEX_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bAssets((?:\.[A-Za-z_]\w*)+)").unwrap());
+
+/// Characters starting the runtime part of a path, e.g. `f":/icons/{name}.png"`.
+const PLACEHOLDERS: &[char] = &['{', '%', '*', '$'];
+
+/// A resource path found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetReference {
+    pub line: u32,
+    /// e.g. `/icons/save.png`
+    pub resource: String,
+    /// Only the start of the path is known, e.g. `/icons/` for
+    /// `f":/icons/{name}.png"`; it covers every resource below.
+    pub dynamic: bool,
+}
+
+/// Finds the resource paths in the text of a `.py`, `.ui`, `.qml` or `.qss` file.
+pub fn find_asset_references(content: &str) -> Vec<AssetReference> {
+    let mut references = Vec::new();
+    for captures in REFERENCE.captures_iter(content) {
+        if captures
+            .get(1)
+            .is_some_and(|scheme| !scheme.as_str().eq_ignore_ascii_case("qrc"))
+        {
+            continue;
+        }
+
+        let path = &captures[2];
+        let (path, dynamic) = match path.find(PLACEHOLDERS) {
+            Some(end) => (&path[..end], true),
+            None => (path, path.ends_with('/')),
+        };
+        let mut resource = clean_resource_path(path);
+        if dynamic && !resource.ends_with('/') && path.ends_with('/') {
+            resource.push('/');
+        }
+        // A bare `:/` says nothing about the resources used
+        if resource == "/" && !dynamic {
+            continue;
+        }
+
+        let start = captures.get(0).map_or(0, |m| m.start());
+        references.push(AssetReference {
+            line: content[..start].matches('\n').count() as u32 + 1,
+            resource,
+            dynamic,
+        });
+    }
+    references
+}
+
+/// Finds the resources a Python source refers to through the asset index,
+/// e.g. `Assets.icons.SAVE_PNG`. Attributes not in the index are skipped.
+pub fn find_index_references(content: &str, index: &AssetIndex) -> Vec<AssetReference> {
+    let mut references = Vec::new();
+    for captures in INDEX_REFERENCE.captures_iter(content) {
+        let attributes: Vec<&str> = captures[1].split('.').skip(1).collect();
+        let Some((resource, dynamic)) = index.resolve(&attributes) else {
+            continue;
+        };
+
+        let start = captures.get(0).map_or(0, |m| m.start());
+        references.push(AssetReference {
+            line: content[..start].matches('\n').count() as u32 + 1,
+            resource,
+            dynamic,
+        });
+    }
+    references
+}
+
+/// Result of [`audit_asset_usage`].
+#[derive(Debug, Default)]
+pub struct AssetUsage {
+    pub diagnostics: Vec<Diagnostic>,
+    pub unused: usize,
+    /// Total size of the unused assets in bytes.
+    pub unused_size: u64,
+}
+
+/// Reports assets no source refers to and references to resources that do
+/// not exist.
+///
+/// `entries` are all files embedded into the resources, only those in
+/// `assets` are reported as unused.
+pub fn audit_asset_usage(
+    entries: &[QrcEntry],
+    assets: &[PathBuf],
+    references: &[(PathBuf, Vec<AssetReference>)],
+    file_size: &dyn Fn(&Path) -> u64,
+) -> AssetUsage {
+    let resources: BTreeSet<String> = entries
+        .iter()
+        .map(|entry| clean_resource_path(&entry.resource))
+        .collect();
+
+    let mut usage = AssetUsage::default();
+    let mut exact = HashSet::new();
+    let mut prefixes = Vec::new();
+    for (path, references) in references {
+        let mut reported = HashSet::new();
+        for reference in references {
+            if reference.dynamic {
+                prefixes.push(reference.resource.as_str());
+                continue;
+            }
+            exact.insert(reference.resource.as_str());
+            // A directory, e.g. for `QDir(":/icons")`
+            let dir = format!("{}/", reference.resource);
+            if !resources.contains(&reference.resource)
+                && !resources.iter().any(|r| r.starts_with(&dir))
+                && reported.insert(&reference.resource)
+            {
+                usage.diagnostics.push(Diagnostic {
+                    path: path.clone(),
+                    line: reference.line,
+                    severity: Severity::Error,
+                    code: "missing-asset",
+                    message: format!("resource `:{}` does not exist", reference.resource),
+                });
+            }
+        }
+    }
+
+    let assets: HashSet<&PathBuf> = assets.iter().collect();
+    // A file embedded under several paths takes its size only once
+    let mut counted = HashSet::new();
+    for entry in entries.iter().filter(|e| assets.contains(&e.file)) {
+        let resource = clean_resource_path(&entry.resource);
+        let used = exact.iter().any(|r| {
+            resource == *r || resource.starts_with(r) && resource[r.len()..].starts_with('/')
+        }) || prefixes.iter().any(|prefix| resource.starts_with(prefix));
+        if used {
+            continue;
+        }
+
+        let size = file_size(&entry.file);
+        usage.unused += 1;
+        if counted.insert(&entry.file) {
+            usage.unused_size += size;
+        }
+        usage.diagnostics.push(Diagnostic {
+            path: entry.file.clone(),
+            line: 1,
+            severity: Severity::Warning,
+            code: "unused-asset",
+            message: format!(
+                "`:{}` is never referenced ({})",
+                resource,
+                format_size(size)
+            ),
+        });
+    }
+
+    usage
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_asset_usage() {
+        let source = r#"
+icon = QIcon(":/icons/save.png")
+view.setSource(QUrl("qrc:///qml/main.qml"))
+flag = QIcon(f":/flags/{lang}.png")
+link = "https://example.com/a.png"
+broken = QPixmap(':/icons/missing.png')
+"#;
+        let references = find_asset_references(source);
+        assert_eq!(
+            references
+                .iter()
+                .map(|r| (r.line, r.resource.as_str(), r.dynamic))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, "/icons/save.png", false),
+                (3, "/qml/main.qml", false),
+                (4, "/flags/", true),
+                (6, "/icons/missing.png", false),
+            ]
+        );
+
+        let qss = "QPushButton { image: url(:/themes/dark/../check.svg); }";
+        assert_eq!(find_asset_references(qss)[0].resource, "/themes/check.svg");
+
+        let entry = |resource: &str, file: &str| QrcEntry {
+            resource: resource.to_string(),
+            file: PathBuf::from(file),
+        };
+        let entries = vec![
+            entry("/icons/save.png", "assets/icons/save.png"),
+            entry("/icons/old.png", "assets/icons/old.png"),
+            entry("/flags/de.png", "assets/flags/de.png"),
+            entry("/qml/main.qml", "assets/qml/main.qml"),
+        ];
+        let assets: Vec<PathBuf> = entries.iter().map(|e| e.file.clone()).collect();
+        let usage = audit_asset_usage(
+            &entries,
+            &assets,
+            &[(PathBuf::from("app/main.py"), references)],
+            &|_| 100,
+        );
+        assert_eq!(usage.unused, 1);
+        assert_eq!(usage.unused_size, 100);
+        assert_eq!(
+            usage
+                .diagnostics
+                .iter()
+                .map(|d| (d.path.to_str().unwrap(), d.line, d.code))
+                .collect::<Vec<_>>(),
+            vec![
+                ("app/main.py", 6, "missing-asset"),
+                ("assets/icons/old.png", 1, "unused-asset"),
+            ]
+        );
+
+        let resources: Vec<String> = entries.iter().map(|e| e.resource.clone()).collect();
+        let index = AssetIndex::new(&resources);
+        let source = "\
+from .resources.assets_index import Assets
+icon = QIcon(Assets.icons.OLD_PNG)
+flags = vars(Assets.flags)
+typo = Assets.icons.SAVE_JPG
+";
+        let references = find_index_references(source, &index);
+        assert_eq!(
+            references
+                .iter()
+                .map(|r| (r.line, r.resource.as_str(), r.dynamic))
+                .collect::<Vec<_>>(),
+            vec![(2, "/icons/old.png", false), (3, "/flags/", true)]
+        );
+        let usage = audit_asset_usage(
+            &entries,
+            &assets,
+            &[(PathBuf::from("app/main.py"), references)],
+            &|_| 100,
+        );
+        assert_eq!(usage.unused, 2);
+        assert!(
+            usage
+                .diagnostics
+                .iter()
+                .all(|d| d.path != Path::new("assets/icons/old.png"))
+        );
+    }
+}
@@ -1,86 +1,472 @@
 use std::{
-    fs::{self, File},
-    io::Write,
-    path::Path,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
+use serde::Deserialize;
 use walkdir::WalkDir;
 
 use crate::{
     cache::Cache,
     errcode::{Errcode, GeneralErrorKind, ToolchainErrorKind},
-    files::Files,
+    files::{Files, PatternSet},
+    layout::Layout,
+    qt::{
+        asset_index::{ASSET_INDEX_MODULE, asset_index_modules},
+        qrc::{
+            QResource, Qrc, QrcEntry, QrcFile, read_qrc_entries, referenced_files, resource_path,
+        },
+        ui::RUNTIME_FORMS_PREFIX,
+    },
     run_tool,
+    toolchain::Toolchain,
+    utils::source_date_epoch,
+    version::{VersionConfig, write_version_py},
 };
 
-macro_rules! my_write {
-    ($filename:expr, $file:expr, $($arg:tt)*) => {
-        write!($file, $($arg)*)
-            .map_err(|e| Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
-                path: $filename.clone(),
-                source: e,
-            }))
-    };
+/// Compression algorithms understood by rcc.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    Zlib,
+    Zstd,
+    /// Highest compression of the available algorithms.
+    Best,
+    /// Store files uncompressed.
+    None,
 }
 
-fn generate_assets_qrc(root: &Path, files: &Files) -> Result<(), Errcode> {
-    let res_dir = root.join("resources");
-    let assets_dir = root.join("assets");
-    let qrc_file = res_dir.join("assets.qrc");
+impl CompressionAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompressionAlgorithm::Zlib => "zlib",
+            CompressionAlgorithm::Zstd => "zstd",
+            CompressionAlgorithm::Best => "best",
+            CompressionAlgorithm::None => "none",
+        }
+    }
+}
 
-    fs::create_dir_all(&res_dir).map_err(|e| {
-        Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
-            path: res_dir,
+/// What `compile_resources` produces from the generated qrc.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceOutput {
+    /// Python module (`resource.py`) registering the resources on import.
+    #[default]
+    Python,
+    /// Binary bundle (`assets.rcc`) for `QResource.registerResource`.
+    Binary,
+    /// Both of the above.
+    Both,
+}
+
+impl ResourceOutput {
+    pub fn python(&self) -> bool {
+        matches!(self, ResourceOutput::Python | ResourceOutput::Both)
+    }
+
+    pub fn binary(&self) -> bool {
+        matches!(self, ResourceOutput::Binary | ResourceOutput::Both)
+    }
+}
+
+/// How assets are partitioned into separate resource modules.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetSplit {
+    /// One module for all assets.
+    #[default]
+    None,
+    /// One module per top-level directory of the assets directory.
+    Directory,
+}
+
+/// Settings from `[tool.pyside-cli.assets]`.
+#[derive(Debug, Clone, Default)]
+pub struct AssetsConfig {
+    /// Directories (relative to the assets directory) mapped to a resource
+    /// prefix, deepest directory first.
+    pub prefixes: Vec<(PathBuf, String)>,
+    /// Languages from `[tool.pyside-cli.i18n]`; `assets/@<lang>/` directories
+    /// for these become language-qualified blocks.
+    pub languages: Vec<String>,
+    pub compression: Option<CompressionAlgorithm>,
+    pub compression_level: Option<u32>,
+    /// Minimum size reduction (percent) for rcc to keep a file compressed.
+    pub compression_threshold: Option<u32>,
+    /// Assets (relative to the assets directory) stored uncompressed.
+    pub no_compress: PatternSet,
+    pub output: ResourceOutput,
+    pub split: AssetSplit,
+    /// Named asset groups, each compiled into its own module. Checked in
+    /// order, before `split`.
+    pub groups: Vec<(String, PatternSet)>,
+}
+
+impl AssetsConfig {
+    /// Global compression flags passed to rcc.
+    pub fn rcc_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match &self.compression {
+            Some(CompressionAlgorithm::None) => args.push("--no-compress".to_string()),
+            Some(algorithm) => {
+                args.push("--compress-algo".to_string());
+                args.push(algorithm.as_str().to_string());
+            }
+            None => {}
+        }
+
+        if let Some(level) = self.compression_level {
+            args.push("--compress".to_string());
+            args.push(level.to_string());
+        }
+
+        if let Some(threshold) = self.compression_threshold {
+            args.push("--threshold".to_string());
+            args.push(threshold.to_string());
+        }
+
+        args
+    }
+
+    /// Everything that affects the compiled resources, stored in the cache.
+    fn fingerprint(&self) -> String {
+        let mut parts = self.rcc_args();
+        parts.push(format!("{:?}", self.output));
+        parts.extend(
+            self.prefixes
+                .iter()
+                .map(|(dir, prefix)| format!("prefix:{}={}", dir.to_string_lossy(), prefix)),
+        );
+        parts.extend(self.languages.iter().map(|lang| format!("lang:{}", lang)));
+        parts.extend(
+            self.no_compress
+                .patterns()
+                .iter()
+                .map(|pattern| format!("no-compress:{}", pattern)),
+        );
+        parts.join(" ")
+    }
+
+    /// Splits an asset path (relative to the assets directory) into its
+    /// resource language and the remaining path.
+    ///
+    /// `@zh_CN/icons/save.png` is served as `:/icons/save.png` when the
+    /// application locale is `zh_CN`.
+    fn resolve_lang<'a>(&self, rel_path: &'a Path) -> (Option<String>, &'a Path) {
+        let mut components = rel_path.components();
+        let Some(lang) = components
+            .next()
+            .and_then(|c| c.as_os_str().to_str())
+            .and_then(|name| name.strip_prefix('@'))
+        else {
+            return (None, rel_path);
+        };
+
+        if !self.languages.iter().any(|l| l == lang) {
+            return (None, rel_path);
+        }
+
+        (Some(lang.to_string()), components.as_path())
+    }
+
+    fn is_split(&self) -> bool {
+        self.split != AssetSplit::None || !self.groups.is_empty()
+    }
+
+    /// Returns the group an asset (relative to the assets directory) belongs
+    /// to, `""` being the default group.
+    ///
+    /// Localized variants (`@zh_CN/icons/...`) follow their unlocalized path.
+    fn group_of(&self, rel_path: &Path) -> (String, String) {
+        let (_, rel_path) = self.resolve_lang(rel_path);
+
+        for (name, patterns) in &self.groups {
+            if patterns.first_match(rel_path).is_some() {
+                return (name.clone(), format!("assets.groups.{}", name));
+            }
+        }
+
+        if self.split == AssetSplit::Directory {
+            let mut components = rel_path.components();
+            let top = components.next();
+            // Files directly in the assets directory stay in the default group
+            if components.next().is_some()
+                && let Some(top) = top
+            {
+                let dir = top.as_os_str().to_string_lossy();
+                return (module_name(&dir), format!("{}/", dir));
+            }
+        }
+
+        (String::new(), String::new())
+    }
+
+    /// Partitions assets into groups, sorted by group name.
+    ///
+    /// Fails when two groups, e.g. the directories `my-icons` and `my_icons`,
+    /// would generate the same module, or a group would replace the index.
+    fn partition(
+        &self,
+        assets_dir: &Path,
+        asset_list: &[PathBuf],
+    ) -> Result<BTreeMap<String, Vec<PathBuf>>, Errcode> {
+        let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        // Module -> the group it was first generated for
+        let mut origins: HashMap<String, String> = HashMap::new();
+        let mut unknown_langs = HashSet::new();
+
+        for asset in asset_list {
+            // Files from elsewhere, e.g. runtime forms, go to the default group
+            let Ok(rel_path) = asset.strip_prefix(assets_dir) else {
+                groups.entry(String::new()).or_default().push(asset.clone());
+                continue;
+            };
+
+            if let Some(lang) = rel_path
+                .components()
+                .next()
+                .and_then(|c| c.as_os_str().to_str())
+                .and_then(|name| name.strip_prefix('@'))
+                && !self.languages.iter().any(|l| l == lang)
+                && unknown_langs.insert(lang.to_string())
+            {
+                log::warn!(
+                    "{} is not listed in i18n languages, treating it as a regular directory.",
+                    lang
+                );
+            }
+
+            let (module, origin) = self.group_of(rel_path);
+            if module == GROUPS_INDEX {
+                return Err(Errcode::GeneralError(
+                    GeneralErrorKind::AssetGroupConflict {
+                        module,
+                        first: origin,
+                        second: "the group index".to_string(),
+                    },
+                ));
+            }
+            let first = origins
+                .entry(module.clone())
+                .or_insert_with(|| origin.clone());
+            if *first != origin {
+                let (first, second) = if *first < origin {
+                    (first.clone(), origin)
+                } else {
+                    (origin, first.clone())
+                };
+                return Err(Errcode::GeneralError(
+                    GeneralErrorKind::AssetGroupConflict {
+                        module,
+                        first,
+                        second,
+                    },
+                ));
+            }
+            groups.entry(module).or_default().push(asset.clone());
+        }
+
+        Ok(groups)
+    }
+
+    /// Path of an asset inside the resource system, e.g. `/icons/save.png`.
+    ///
+    /// Localized variants share the path of the unlocalized file.
+    pub fn resource_path(&self, assets_dir: &Path, asset: &Path) -> String {
+        let rel_path = asset.strip_prefix(assets_dir).unwrap_or(asset);
+        let (_, rel_path) = self.resolve_lang(rel_path);
+        let (prefix, alias) = self.resolve_prefix(rel_path);
+        resource_path(prefix.as_deref(), &alias.to_string_lossy())
+    }
+
+    /// Splits an asset path (relative to the assets directory) into its
+    /// resource prefix and alias. `None` means the default, unprefixed block.
+    fn resolve_prefix<'a>(&self, rel_path: &'a Path) -> (Option<String>, &'a Path) {
+        for (dir, prefix) in &self.prefixes {
+            if let Ok(alias) = rel_path.strip_prefix(dir) {
+                let prefix = (prefix != "/").then(|| prefix.clone());
+                return (prefix, alias);
+            }
+        }
+        (None, rel_path)
+    }
+}
+
+/// Turns a directory name into a valid Python module suffix.
+fn module_name(name: &str) -> String {
+    let mut module: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if module.starts_with(|c: char| c.is_ascii_digit()) {
+        module.insert(0, '_');
+    }
+    module
+}
+
+/// Files generated for one asset group.
+struct GroupOutputs {
+    qrc: PathBuf,
+    python: PathBuf,
+    binary: PathBuf,
+}
+
+impl GroupOutputs {
+    /// `assets.qrc`, `resource.py` and `assets.rcc` for the default group,
+    /// `assets_<group>.qrc`, `resource_<group>.py` and `assets_<group>.rcc`
+    /// for the others.
+    fn new(res_dir: &Path, group: &str) -> Self {
+        let suffix = if group.is_empty() {
+            String::new()
+        } else {
+            format!("_{}", group)
+        };
+
+        Self {
+            qrc: res_dir.join(format!("assets{}.qrc", suffix)),
+            python: res_dir.join(format!("resource{}.py", suffix)),
+            binary: res_dir.join(format!("assets{}.rcc", suffix)),
+        }
+    }
+
+    fn from_qrc(qrc_file: &Path) -> Option<Self> {
+        let res_dir = qrc_file.parent()?;
+        let stem = qrc_file.file_stem()?.to_str()?;
+        let group = match stem.strip_prefix("assets") {
+            Some("") => "",
+            Some(rest) => rest.strip_prefix('_')?,
+            None => return None,
+        };
+        Some(Self::new(res_dir, group))
+    }
+
+    /// Removes the outputs of a group that no longer exists.
+    fn remove(&self) -> Result<(), Errcode> {
+        for path in [&self.qrc, &self.python, &self.binary] {
+            if path.exists() {
+                log::info!("Removing stale {}.", path.display());
+                fs::remove_file(path).map_err(|e| {
+                    Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
+                        path: path.clone(),
+                        source: e,
+                    })
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Group whose module name is taken by the index of all groups.
+const GROUPS_INDEX: &str = "groups";
+
+/// Writes `resource_groups.py`, which imports resource modules on demand.
+fn generate_groups_index(index_file: &Path, groups: &[&String]) -> Result<(), Errcode> {
+    let res_dir = index_file.parent().unwrap_or(Path::new(""));
+
+    let mut content = String::from(
+        "# Generated by pyside-cli, do not edit.\n\
+         import importlib\n\
+         \n\
+         # Group name -> resource module, \"\" is the default group\n\
+         GROUPS = {\n",
+    );
+    for group in groups {
+        let module = GroupOutputs::new(res_dir, group)
+            .python
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        content.push_str(&format!("    \"{}\": \".{}\",\n", group, module));
+    }
+    content.push_str(
+        "}\n\
+         \n\
+         \n\
+         def load(name):\n\
+         \x20   \"\"\"Register the resources of one group.\"\"\"\n\
+         \x20   importlib.import_module(GROUPS[name], __package__)\n\
+         \n\
+         \n\
+         def load_all():\n\
+         \x20   \"\"\"Register the resources of every group.\"\"\"\n\
+         \x20   for name in GROUPS:\n\
+         \x20       load(name)\n",
+    );
+
+    fs::write(index_file, content).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+            path: index_file.to_path_buf(),
             source: e,
         })
-    })?;
+    })
+}
+
+fn generate_assets_qrc(
+    root: &Path,
+    layout: &Layout,
+    config: &AssetsConfig,
+    asset_list: &[PathBuf],
+    qrc_file: &Path,
+) -> Result<(), Errcode> {
+    let res_dir = layout.resources_dir(root);
+    let assets_dir = layout.assets_dir(root);
+    let ui_dir = layout.ui_dir(root);
 
-    let mut f = File::create(&qrc_file).map_err(|e| {
+    fs::create_dir_all(&res_dir).map_err(|e| {
         Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
-            path: qrc_file.clone(),
+            path: res_dir.clone(),
             source: e,
         })
     })?;
 
-    my_write!(
-        qrc_file,
-        f,
-        "<!DOCTYPE RCC>
-<RCC version=\"1.0\">
-<qresource>\n"
-    )?;
+    // One block per language and prefix, the unqualified blocks first
+    let mut resources: BTreeMap<(Option<String>, Option<String>), QResource> = BTreeMap::new();
+    for asset in asset_list {
+        if !asset.starts_with(&assets_dir)
+            && let Ok(alias) = asset.strip_prefix(&ui_dir)
+        {
+            let prefix = Some(RUNTIME_FORMS_PREFIX.to_string());
+            resources
+                .entry((None, prefix.clone()))
+                .or_insert_with(|| QResource {
+                    prefix,
+                    ..Default::default()
+                })
+                .files
+                .push(QrcFile::new(asset, alias, &res_dir)?);
+            continue;
+        }
 
-    for asset in &files.asset_list {
-        // alias = path relative to assets/
-        let alias = asset
-            .strip_prefix(&assets_dir)
-            .unwrap_or(asset)
-            .to_string_lossy()
-            .replace('\\', "/");
-        // rel_path = ../assets/xxx/yyy
-        let rel_path = Path::new("..")
-            .join("assets")
-            .join(&alias)
-            .to_string_lossy()
-            .replace('\\', "/");
-        my_write!(
-            qrc_file,
-            f,
-            "  <file alias=\"{}\">{}</file>\n",
-            alias,
-            rel_path
-        )?;
-    }
+        // alias = path relative to assets/ or to the mapped directory
+        let rel_path = asset.strip_prefix(&assets_dir).unwrap_or(asset);
+        let no_compress = config.no_compress.first_match(rel_path).is_some();
+        let (lang, rel_path) = config.resolve_lang(rel_path);
+        let (prefix, alias) = config.resolve_prefix(rel_path);
 
-    my_write!(
-        qrc_file,
-        f,
-        "</qresource>
-</RCC>"
-    )?;
+        let mut file = QrcFile::new(asset, alias, &res_dir)?;
+        if no_compress {
+            file.compression = Some(CompressionAlgorithm::None);
+        }
+        resources
+            .entry((lang.clone(), prefix.clone()))
+            .or_insert_with(|| QResource {
+                prefix,
+                lang,
+                ..Default::default()
+            })
+            .files
+            .push(file);
+    }
 
-    Ok(())
+    let qrc = Qrc {
+        resources: resources.into_values().collect(),
+    };
+    qrc.write(qrc_file)
 }
 
 fn touch_init_py(resources_dir: &Path) -> Result<(), Errcode> {
@@ -114,80 +500,491 @@ fn touch_init_py(resources_dir: &Path) -> Result<(), Errcode> {
     Ok(())
 }
 
-fn touch_version_py(resources_dir: &Path, git: &Path) -> Result<(), Errcode> {
-    let version_py = resources_dir.join("version.py");
-    let version = get_last_tag(git, "0.0.0.0");
+/// Timestamp recorded by rcc for every embedded file.
+///
+/// `SOURCE_DATE_EPOCH` wins; otherwise the HEAD commit time is used, so that
+/// checkouts of the same commit produce identical resource modules regardless
+/// of file modification times.
+fn resource_timestamp(git: &Path) -> Option<u64> {
+    if let Some(epoch) = source_date_epoch() {
+        return Some(epoch);
+    }
 
-    let mut f = File::create(&version_py).map_err(|e| {
-        Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
-            path: version_py.clone(),
-            source: e,
-        })
-    })?;
-    my_write!(version_py, f, "__version__ = '{}'\n", version)?;
+    let output = Command::new(git)
+        .args(["log", "-1", "--format=%ct"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Every path a target can load from the resource system, e.g.
+/// `/icons/save.png`, mapped to the file it comes from.
+///
+/// Covers the generated asset qrc as well as hand-written qrc files.
+pub fn resource_index(
+    root: &Path,
+    layout: &Layout,
+    config: &AssetsConfig,
+    files: &Files,
+) -> Result<BTreeMap<String, PathBuf>, Errcode> {
+    Ok(resource_entries(root, layout, config, files)?
+        .into_iter()
+        .map(|entry| (entry.resource, entry.file))
+        .collect())
+}
+
+/// Every file embedded into the resources with its path, localized variants
+/// share the path of the unlocalized file.
+pub fn resource_entries(
+    root: &Path,
+    layout: &Layout,
+    config: &AssetsConfig,
+    files: &Files,
+) -> Result<Vec<QrcEntry>, Errcode> {
+    let mut entries = Vec::new();
+    for qrc_file in &files.qrc_list {
+        entries.extend(read_qrc_entries(qrc_file)?);
+    }
 
+    let assets_dir = layout.assets_dir(root);
+    for asset in &files.asset_list {
+        entries.push(QrcEntry {
+            resource: config.resource_path(&assets_dir, asset),
+            file: asset.clone(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Locations of the binary resource bundles of a target.
+pub fn binary_resource_paths(
+    root: &Path,
+    layout: &Layout,
+    config: &AssetsConfig,
+    files: &Files,
+) -> Result<Vec<PathBuf>, Errcode> {
+    let res_dir = layout.resources_dir(root);
+    let asset_list: Vec<PathBuf> = files
+        .asset_list
+        .iter()
+        .chain(&files.runtime_ui_list)
+        .cloned()
+        .collect();
+    Ok(config
+        .partition(&layout.assets_dir(root), &asset_list)?
+        .keys()
+        .map(|group| GroupOutputs::new(&res_dir, group).binary)
+        .collect())
+}
+
+/// Runs rcc on `qrc_file`, writing Python code or a binary bundle to `output`.
+fn run_rcc(
+    rcc: &Path,
+    args: &[String],
+    qrc_file: &Path,
+    output: &Path,
+    timestamp: Option<u64>,
+) -> Result<(), Errcode> {
+    let mut cmd = Command::new(rcc);
+    cmd.args(args).arg(qrc_file).arg("-o").arg(output);
+    if let Some(timestamp) = timestamp {
+        cmd.env("QT_RCC_SOURCE_DATE_OVERRIDE", timestamp.to_string());
+    }
+    run_tool!(&rcc, cmd);
     Ok(())
 }
 
-pub fn get_last_tag(git: &Path, default: &str) -> String {
-    let output = Command::new(git)
-        .args(["describe", "--tags", "--abbrev=0", "--first-parent"])
-        .stderr(Stdio::null())
-        .output();
+/// Compiles every hand-written qrc to `<resources>/<name>_rc.py`.
+fn compile_qrc_files(
+    res_dir: &Path,
+    config: &AssetsConfig,
+    rcc: &Path,
+    timestamp: Option<u64>,
+    qrc_files: &[(PathBuf, Vec<PathBuf>)],
+    cache: &mut Cache,
+) -> Result<(), Errcode> {
+    let mut outputs: HashMap<PathBuf, &Path> = HashMap::new();
 
-    let output = match output {
-        Ok(o) if o.status.success() => o,
-        _ => return default.to_string(),
-    };
+    for (qrc_file, referenced) in qrc_files {
+        let stem = qrc_file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or(Errcode::GeneralError(GeneralErrorKind::FileNameInvalid {
+                name: qrc_file.clone(),
+            }))?;
+        let output_file = res_dir.join(format!("{}_rc.py", stem));
 
-    let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if let Some(first) = outputs.insert(output_file.clone(), qrc_file) {
+            return Err(Errcode::GeneralError(GeneralErrorKind::OutputConflict {
+                output: output_file,
+                first: first.to_path_buf(),
+                second: qrc_file.clone(),
+            }));
+        }
 
-    if tag.is_empty() {
-        default.to_string()
-    } else {
-        tag
+        if !cache.check_qrc_file(qrc_file, referenced) {
+            log::info!("{} is up to date.", qrc_file.display());
+            continue;
+        }
+
+        run_rcc(rcc, &config.rcc_args(), qrc_file, &output_file, timestamp)?;
+        log::info!(
+            "Compiled {} to {}.",
+            qrc_file.display(),
+            output_file.display()
+        );
     }
+
+    Ok(())
 }
 
 pub fn compile_resources(
     root: &Path,
-    rcc: &Path,
-    git: &Path,
+    layout: &Layout,
+    config: &AssetsConfig,
+    version: &VersionConfig,
+    toolchain: &Toolchain,
     files: &Files,
     cache: &mut Cache,
 ) -> Result<(), Errcode> {
-    if files.asset_list.is_empty() {
-        log::info!("No assets found, skipping.");
-        return Ok(());
-    }
+    let rcc = toolchain
+        .rcc
+        .as_ref()
+        .ok_or(Errcode::ToolchainError(ToolchainErrorKind::RccNotFound))?;
+    let git = toolchain
+        .git
+        .as_ref()
+        .ok_or(Errcode::ToolchainError(ToolchainErrorKind::GitNotFound))?;
+    // pyside6-rcc always generates Python, binary bundles need Qt's own rcc
+    let qt_rcc = if config.output.binary() {
+        let qt_rcc = toolchain
+            .qt_rcc
+            .as_ref()
+            .ok_or(Errcode::ToolchainError(ToolchainErrorKind::QtRccNotFound))?;
+        Some(qt_rcc)
+    } else {
+        None
+    };
 
-    if !cache.check_all_assets(files) {
-        log::info!("Assets are up to date, skipping.");
-        return Ok(());
+    let res_dir = layout.resources_dir(root);
+    let timestamp = resource_timestamp(git);
+    if let Some(timestamp) = timestamp {
+        log::debug!("Using resource timestamp {}.", timestamp);
     }
 
-    generate_assets_qrc(root, files)?;
+    let qrc_files = files
+        .qrc_list
+        .iter()
+        .map(|qrc| referenced_files(qrc).map(|refs| (qrc.clone(), refs)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if cache.check_asset_options(&config.fingerprint()) {
+        log::info!("Asset settings changed, recompiling all resources.");
+    }
 
-    let res_dir = root.join("resources");
-    let py_res_file = res_dir.join("resource.py");
-    if !res_dir.exists() {
+    if !qrc_files.is_empty() {
         fs::create_dir_all(&res_dir).map_err(|e| {
             Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
                 path: res_dir.clone(),
                 source: e,
             })
         })?;
-    };
-    run_tool!(
-        &rcc,
-        Command::new(&rcc)
-            .arg(root.join("resources").join("assets.qrc"))
-            .arg("-o")
-            .arg(py_res_file)
-    );
+        compile_qrc_files(&res_dir, config, rcc, timestamp, &qrc_files, cache)?;
+    }
+
+    // Runtime forms are embedded like assets
+    let asset_list: Vec<PathBuf> = files
+        .asset_list
+        .iter()
+        .chain(&files.runtime_ui_list)
+        .cloned()
+        .collect();
 
-    touch_version_py(&res_dir, git)?;
+    if asset_list.is_empty() {
+        log::info!("No assets found, skipping.");
+    } else {
+        compile_asset_groups(
+            root,
+            layout,
+            config,
+            (rcc, qt_rcc),
+            timestamp,
+            &asset_list,
+            cache,
+        )?;
+    }
+
+    // The version module is useful even to targets without assets
+    fs::create_dir_all(&res_dir).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
+            path: res_dir.clone(),
+            source: e,
+        })
+    })?;
+    write_version_py(&res_dir, version, git)?;
+    write_asset_index(&res_dir, &resource_index(root, layout, config, files)?)?;
     touch_init_py(&res_dir)?;
 
     Ok(())
 }
+
+/// Writes `assets_index.py` and its stub, or removes them when there are no assets.
+fn write_asset_index(res_dir: &Path, resources: &BTreeMap<String, PathBuf>) -> Result<(), Errcode> {
+    let module_file = res_dir.join(ASSET_INDEX_MODULE);
+    let stub_file = module_file.with_extension("pyi");
+
+    if resources.is_empty() {
+        for stale in [&module_file, &stub_file] {
+            if stale.exists() {
+                log::info!("Removing stale {}.", stale.display());
+                fs::remove_file(stale).map_err(|e| {
+                    Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
+                        path: stale.clone(),
+                        source: e,
+                    })
+                })?;
+            }
+        }
+        return Ok(());
+    }
+
+    let (module, stub) = asset_index_modules(resources.keys());
+    for (path, content) in [(&module_file, module), (&stub_file, stub)] {
+        // Unchanged files keep their modification time
+        if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+            continue;
+        }
+        fs::write(path, content).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+                path: path.clone(),
+                source: e,
+            })
+        })?;
+        log::info!("Asset index written to {}.", path.display());
+    }
+
+    Ok(())
+}
+
+/// Generates and compiles the qrc of every asset group that changed.
+fn compile_asset_groups(
+    root: &Path,
+    layout: &Layout,
+    config: &AssetsConfig,
+    (rcc, qt_rcc): (&Path, Option<&PathBuf>),
+    timestamp: Option<u64>,
+    asset_list: &[PathBuf],
+    cache: &mut Cache,
+) -> Result<(), Errcode> {
+    let res_dir = layout.resources_dir(root);
+
+    let groups = config.partition(&layout.assets_dir(root), asset_list)?;
+    let qrc_files: Vec<PathBuf> = groups
+        .keys()
+        .map(|group| GroupOutputs::new(&res_dir, group).qrc)
+        .collect();
+    // Removed groups outdate the index as well
+    let stale_groups = cache.retain_asset_groups(&res_dir, &qrc_files);
+    for stale in &stale_groups {
+        if let Some(outputs) = GroupOutputs::from_qrc(stale) {
+            outputs.remove()?;
+        }
+    }
+
+    let mut regenerated = !stale_groups.is_empty();
+    for (group, assets) in &groups {
+        let outputs = GroupOutputs::new(&res_dir, group);
+
+        if !cache.check_asset_group(&outputs.qrc, assets) {
+            log::info!("{} is up to date.", outputs.qrc.display());
+            continue;
+        }
+
+        generate_assets_qrc(root, layout, config, assets, &outputs.qrc)?;
+
+        if config.output.python() {
+            run_rcc(
+                rcc,
+                &config.rcc_args(),
+                &outputs.qrc,
+                &outputs.python,
+                timestamp,
+            )?;
+        }
+
+        if let Some(qt_rcc) = qt_rcc {
+            let mut args = vec!["--binary".to_string()];
+            args.extend(config.rcc_args());
+            run_rcc(qt_rcc, &args, &outputs.qrc, &outputs.binary, timestamp)?;
+            log::info!(
+                "Binary resource bundle written to {}.",
+                outputs.binary.display()
+            );
+        }
+
+        regenerated = true;
+    }
+
+    if !regenerated {
+        log::info!("Assets are up to date, skipping.");
+        return Ok(());
+    }
+
+    let index_file = GroupOutputs::new(&res_dir, GROUPS_INDEX).python;
+    if config.is_split() && config.output.python() {
+        generate_groups_index(&index_file, &groups.keys().collect::<Vec<_>>())?;
+    } else if index_file.exists() {
+        log::info!("Removing stale {}.", index_file.display());
+        fs::remove_file(&index_file).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
+                path: index_file.clone(),
+                source: e,
+            })
+        })?;
+    }
+
+    Ok(())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_resolve_lang_and_prefix() {
+        let config = AssetsConfig {
+            prefixes: vec![(PathBuf::from("icons"), "/icons".to_string())],
+            languages: vec!["zh_CN".to_string()],
+            ..Default::default()
+        };
+
+        let (lang, rest) = config.resolve_lang(Path::new("@zh_CN/icons/save.png"));
+        assert_eq!(lang.as_deref(), Some("zh_CN"));
+        let (prefix, alias) = config.resolve_prefix(rest);
+        assert_eq!(prefix.as_deref(), Some("/icons"));
+        assert_eq!(alias, Path::new("save.png"));
+
+        let (lang, rest) = config.resolve_lang(Path::new("@fr/logo.png"));
+        assert_eq!(lang, None);
+        assert_eq!(rest, Path::new("@fr/logo.png"));
+
+        let assets_dir = Path::new("app/assets");
+        let resource = |asset: &str| config.resource_path(assets_dir, Path::new(asset));
+        assert_eq!(
+            resource("app/assets/@zh_CN/icons/save.png"),
+            "/icons/save.png"
+        );
+        assert_eq!(resource("app/assets/logo.png"), "/logo.png");
+    }
+
+    #[test]
+    fn test_partition_assets() {
+        let config = AssetsConfig {
+            languages: vec!["zh_CN".to_string()],
+            split: AssetSplit::Directory,
+            groups: vec![(
+                "media".to_string(),
+                PatternSet::new(&["sounds/**".to_string()]).unwrap(),
+            )],
+            ..Default::default()
+        };
+        let assets_dir = Path::new("app/assets");
+        let assets: Vec<PathBuf> = [
+            "app/assets/logo.png",
+            "app/assets/icons/save.png",
+            "app/assets/@zh_CN/icons/save.png",
+            "app/assets/sounds/click.wav",
+            "app/assets/2d-sprites/hero.png",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+
+        let groups = config.partition(assets_dir, &assets).unwrap();
+        assert_eq!(
+            groups.keys().collect::<Vec<_>>(),
+            vec!["", "_2d_sprites", "icons", "media"]
+        );
+        assert_eq!(groups["icons"].len(), 2);
+
+        let outputs = GroupOutputs::new(Path::new("app/resources"), "icons");
+        assert_eq!(outputs.python, Path::new("app/resources/resource_icons.py"));
+        let outputs = GroupOutputs::from_qrc(&outputs.qrc).unwrap();
+        assert_eq!(outputs.binary, Path::new("app/resources/assets_icons.rcc"));
+
+        let assets: Vec<PathBuf> = ["app/assets/my_icons/a.png", "app/assets/my-icons/b.png"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert!(matches!(
+            config.partition(assets_dir, &assets),
+            Err(Errcode::GeneralError(GeneralErrorKind::AssetGroupConflict {
+                module,
+                first,
+                second,
+            })) if module == "my_icons" && first == "my-icons/" && second == "my_icons/"
+        ));
+
+        let assets = vec![PathBuf::from("app/assets/groups/a.png")];
+        assert!(matches!(
+            config.partition(assets_dir, &assets),
+            Err(Errcode::GeneralError(GeneralErrorKind::AssetGroupConflict {
+                module,
+                ..
+            })) if module == GROUPS_INDEX
+        ));
+    }
+
+    #[test]
+    fn test_removed_group_updates_index() {
+        let root = std::env::temp_dir().join(format!("pyside-cli-groups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let layout = Layout::default();
+        let res_dir = layout.resources_dir(&root);
+        let icon = layout.assets_dir(&root).join("icons/save.png");
+        let sound = layout.assets_dir(&root).join("sounds/click.wav");
+        for file in [&icon, &sound] {
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, "").unwrap();
+        }
+        let config = AssetsConfig {
+            split: AssetSplit::Directory,
+            ..Default::default()
+        };
+
+        // Both groups compiled before, then every sound is removed
+        let mut cache = Cache::default();
+        let icons = GroupOutputs::new(&res_dir, "icons");
+        let sounds = GroupOutputs::new(&res_dir, "sounds");
+        cache.check_asset_group(&icons.qrc, std::slice::from_ref(&icon));
+        cache.check_asset_group(&sounds.qrc, std::slice::from_ref(&sound));
+        fs::create_dir_all(&res_dir).unwrap();
+        fs::write(&sounds.python, "").unwrap();
+        let index_file = res_dir.join("resource_groups.py");
+        generate_groups_index(&index_file, &[&"icons".to_string(), &"sounds".to_string()]).unwrap();
+
+        // The icons are up to date, rcc is never run
+        compile_asset_groups(
+            &root,
+            &layout,
+            &config,
+            (Path::new("rcc"), None),
+            None,
+            &[icon],
+            &mut cache,
+        )
+        .unwrap();
+
+        let index = fs::read_to_string(&index_file).unwrap();
+        assert!(!sounds.python.exists());
+        assert!(index.contains("\"icons\": \".resource_icons\""));
+        assert!(!index.contains("sounds"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,11 +1,15 @@
 use std::{fs, path::Path, process::Command};
 
+use serde::Serialize;
+
 use crate::errcode::{Errcode, GeneralErrorKind, ToolchainErrorKind};
+use crate::layout::Layout;
 use crate::run_tool;
 use crate::{cache::Cache, files::Files};
 
 pub fn generate_i18n_ts_files(
     root: &Path,
+    layout: &Layout,
     lupdate: &Path,
     files: &Files,
     languages: Vec<String>,
@@ -15,7 +19,7 @@ pub fn generate_i18n_ts_files(
         return Ok(());
     }
 
-    let i18n_dir = root.join("i18n");
+    let i18n_dir = layout.i18n_dir(root);
     fs::create_dir_all(&i18n_dir).map_err(|e| {
         Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
             path: i18n_dir.clone(),
@@ -48,11 +52,12 @@ pub fn generate_i18n_ts_files(
 
 pub fn compile_i18n_ts_files(
     root: &Path,
+    layout: &Layout,
     lrelease: &Path,
     files: &Files,
     cache: &mut Cache,
 ) -> Result<(), Errcode> {
-    let qm_root = root.join("assets").join("i18n");
+    let qm_root = layout.qm_dir(root);
     fs::create_dir_all(&qm_root).map_err(|e| {
         Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
             path: qm_root.clone(),
@@ -86,3 +91,118 @@ pub fn compile_i18n_ts_files(
 
     Ok(())
 }
+
+/// Message counts of one translation file (.ts).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TsStatus {
+    pub language: String,
+    pub finished: usize,
+    pub unfinished: usize,
+    /// Gone from the sources, kept by lupdate in case they come back.
+    pub vanished: usize,
+    /// Marked obsolete by older lupdate versions.
+    pub obsolete: usize,
+}
+
+impl TsStatus {
+    /// Share of the current messages that are finished, in percent.
+    pub fn completeness(&self) -> f64 {
+        let total = self.finished + self.unfinished;
+        if total == 0 {
+            return 100.0;
+        }
+        self.finished as f64 * 100.0 / total as f64
+    }
+}
+
+/// Counts the messages of a .ts file by state, `language` is used when the
+/// file does not name its own.
+pub fn parse_ts_status(xml: &str, language: &str) -> Result<TsStatus, String> {
+    // lupdate writes `<!DOCTYPE TS>`
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let doc = roxmltree::Document::parse_with_options(xml, options).map_err(|e| e.to_string())?;
+    let ts = doc.root_element();
+    if !ts.has_tag_name("TS") {
+        return Err("root element is not <TS>".to_string());
+    }
+
+    let mut status = TsStatus {
+        language: ts
+            .attribute("language")
+            .filter(|l| !l.is_empty())
+            .unwrap_or(language)
+            .to_string(),
+        ..Default::default()
+    };
+    for message in ts.descendants().filter(|n| n.has_tag_name("message")) {
+        let translation = message.children().find(|n| n.has_tag_name("translation"));
+        match translation.and_then(|t| t.attribute("type")) {
+            Some("vanished") => status.vanished += 1,
+            Some("obsolete") => status.obsolete += 1,
+            Some("unfinished") => status.unfinished += 1,
+            // A message without translation was never looked at
+            _ if translation.is_none() => status.unfinished += 1,
+            _ => status.finished += 1,
+        }
+    }
+    Ok(status)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_parse_ts_status() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE TS>
+<TS version="2.1" language="zh_CN">
+<context>
+    <name>MainWindow</name>
+    <message>
+        <source>Open</source>
+        <translation>打开</translation>
+    </message>
+    <message numerus="yes">
+        <source>%n file(s)</source>
+        <translation>
+            <numerusform>%n 个文件</numerusform>
+        </translation>
+    </message>
+    <message>
+        <source>Save</source>
+        <translation type="unfinished"></translation>
+    </message>
+    <message>
+        <source>Quit</source>
+        <translation type="vanished">退出</translation>
+    </message>
+    <message>
+        <source>Help</source>
+        <translation type="obsolete">帮助</translation>
+    </message>
+</context>
+</TS>
+"#;
+        let status = parse_ts_status(xml, "fallback").unwrap();
+        assert_eq!(
+            status,
+            TsStatus {
+                language: "zh_CN".to_string(),
+                finished: 2,
+                unfinished: 1,
+                vanished: 1,
+                obsolete: 1,
+            }
+        );
+        assert!((status.completeness() - 200.0 / 3.0).abs() < 1e-9);
+
+        let empty = parse_ts_status(r#"<TS version="2.1"/>"#, "de_DE").unwrap();
+        assert_eq!(empty.language, "de_DE");
+        assert_eq!(empty.completeness(), 100.0);
+        assert!(parse_ts_status("<ui/>", "de_DE").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::qt::ui_form::{UiForm, UiObject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A problem found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub line: u32,
+    pub severity: Severity,
+    /// Short identifier of the check, e.g. `duplicate-name`.
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} [{}]",
+            self.path.display(),
+            self.line,
+            self.severity,
+            self.message,
+            self.code
+        )
+    }
+}
+
+/// What a form is checked against.
+pub struct LintContext<'a> {
+    /// Resource paths the target provides, e.g. `/icons/save.png`.
+    pub resources: &'a BTreeMap<String, PathBuf>,
+    /// Whether a Python module can be imported from the project.
+    pub module_exists: &'a dyn Fn(&str) -> bool,
+}
+
+/// Runs every static check on a parsed form.
+pub fn lint_ui_form(path: &Path, form: &UiForm, context: &LintContext) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut report = |line: u32, severity: Severity, code: &'static str, message: String| {
+        diagnostics.push(Diagnostic {
+            path: path.to_path_buf(),
+            line,
+            severity,
+            code,
+            message,
+        });
+    };
+
+    // Object names become attributes of the Ui class and must be unique
+    let mut names: HashMap<&str, &UiObject> = HashMap::new();
+    for object in std::iter::once(&form.root).chain(&form.objects) {
+        if let Some(first) = names.get(object.name.as_str()) {
+            report(
+                object.line,
+                Severity::Error,
+                "duplicate-name",
+                format!(
+                    "object name `{}` is already used on line {}",
+                    object.name, first.line
+                ),
+            );
+        } else {
+            names.insert(&object.name, object);
+        }
+    }
+
+    for custom in &form.custom_widgets {
+        match custom.module() {
+            None => report(
+                custom.line,
+                Severity::Error,
+                "unresolved-promotion",
+                format!("promoted widget `{}` has no header", custom.class),
+            ),
+            Some(module) if !(context.module_exists)(&module) => report(
+                custom.line,
+                Severity::Error,
+                "unresolved-promotion",
+                format!(
+                    "module `{}` of promoted widget `{}` not found",
+                    module, custom.class
+                ),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    for connection in &form.connections {
+        for (role, name) in [
+            ("sender", &connection.sender),
+            ("receiver", &connection.receiver),
+        ] {
+            if !names.contains_key(name.as_str()) {
+                report(
+                    connection.line,
+                    Severity::Error,
+                    "unknown-connection-object",
+                    format!(
+                        "connection {} `{}` does not exist ({} -> {})",
+                        role, name, connection.signal, connection.slot
+                    ),
+                );
+            }
+        }
+    }
+
+    for label in form.objects.iter().filter(|o| o.class == "QLabel") {
+        match label.property("buddy") {
+            Some(buddy) if !names.contains_key(buddy.value.as_str()) => report(
+                buddy.line,
+                Severity::Error,
+                "unknown-buddy",
+                format!(
+                    "buddy `{}` of label `{}` does not exist",
+                    buddy.value, label.name
+                ),
+            ),
+            Some(_) => {}
+            None => {
+                if let Some(text) = label.property("text")
+                    && has_mnemonic(&text.value)
+                {
+                    report(
+                        label.line,
+                        Severity::Warning,
+                        "missing-buddy",
+                        format!(
+                            "label `{}` has a shortcut in `{}` but no buddy",
+                            label.name, text.value
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for reference in &form.resource_references {
+        if !context.resources.contains_key(&reference.resource)
+            && reported.insert(&reference.resource)
+        {
+            report(
+                reference.line,
+                Severity::Error,
+                "missing-resource",
+                format!("resource `:{}` is not in any qrc", reference.resource),
+            );
+        }
+    }
+
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+/// Whether a label text defines a keyboard shortcut, `&&` being a literal `&`.
+pub fn has_mnemonic(text: &str) -> bool {
+    mnemonic(text).is_some()
+}
+
+/// The shortcut character of a text, e.g. `n` for `&Name`.
+pub fn mnemonic(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            match chars.next() {
+                Some('&') => continue,
+                Some(c) if !c.is_whitespace() => return Some(c.to_ascii_lowercase()),
+                _ => return None,
+            }
+        }
+    }
+    None
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_lint_ui_form() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>Form</class>
+ <widget class="QWidget" name="Form">
+  <layout class="QVBoxLayout" name="layout">
+   <item>
+    <widget class="QLabel" name="nameLabel">
+     <property name="text"><string>&amp;Name</string></property>
+    </widget>
+   </item>
+   <item>
+    <widget class="QLabel" name="mailLabel">
+     <property name="buddy"><cstring>mailEdit</cstring></property>
+    </widget>
+   </item>
+   <item>
+    <widget class="QPushButton" name="nameLabel">
+     <property name="icon"><iconset><normaloff>:/icons/missing.png</normaloff></iconset></property>
+     <property name="styleSheet"><string>background: url(:/icons/ok.png);</string></property>
+    </widget>
+   </item>
+   <item>
+    <widget class="PlotWidget" name="plot"/>
+   </item>
+  </layout>
+ </widget>
+ <customwidgets>
+  <customwidget>
+   <class>PlotWidget</class>
+   <extends>QWidget</extends>
+   <header>app/widgets/plot.h</header>
+  </customwidget>
+ </customwidgets>
+ <connections>
+  <connection>
+   <sender>okButton</sender>
+   <signal>clicked()</signal>
+   <receiver>Form</receiver>
+   <slot>close()</slot>
+  </connection>
+ </connections>
+</ui>
+"#;
+        let form = UiForm::parse(xml).unwrap();
+        let resources = BTreeMap::from([("/icons/ok.png".to_string(), PathBuf::new())]);
+        let context = LintContext {
+            resources: &resources,
+            module_exists: &|module| module == "app.widgets.other",
+        };
+
+        let diagnostics = lint_ui_form(Path::new("form.ui"), &form, &context);
+        let codes: Vec<(u32, &str)> = diagnostics.iter().map(|d| (d.line, d.code)).collect();
+        assert_eq!(
+            codes,
+            [
+                (7, "missing-buddy"),
+                (13, "unknown-buddy"),
+                (17, "duplicate-name"),
+                (18, "missing-resource"),
+                (28, "unresolved-promotion"),
+                (35, "unknown-connection-object"),
+            ]
+        );
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "form.ui:17: error: object name `nameLabel` is already used on line 7 [duplicate-name]"
+        );
+    }
+
+    #[test]
+    fn test_mnemonic() {
+        assert_eq!(mnemonic("&Name"), Some('n'));
+        assert_eq!(mnemonic("Save && &Quit"), Some('q'));
+        assert_eq!(mnemonic("Tom && Jerry"), None);
+        assert_eq!(mnemonic("Trailing &"), None);
+    }
+}
@@ -1,3 +1,13 @@
+pub mod a11y;
+pub mod asset_index;
+pub mod asset_usage;
 pub mod assets;
 pub mod i18n;
+pub mod lint;
+pub mod qrc;
 pub mod ui;
+pub mod ui_diff;
+pub mod ui_fmt;
+pub mod ui_form;
+pub mod ui_index;
+pub mod ui_loader;
@@ -0,0 +1,386 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errcode::{Errcode, GeneralErrorKind},
+    qt::assets::CompressionAlgorithm,
+    utils::{escape_xml, normalize_path, relative_path},
+};
+
+/// A single `<file>` entry.
+#[derive(Debug, Clone)]
+pub struct QrcFile {
+    /// Name inside the resource system, e.g. `icons/save.png`.
+    pub alias: String,
+    /// Path of the file relative to the qrc file.
+    pub path: String,
+    /// Path of the file relative to the working directory.
+    pub source: PathBuf,
+    /// Per-file override of the global rcc compression.
+    pub compression: Option<CompressionAlgorithm>,
+}
+
+impl QrcFile {
+    /// Creates an entry for `source`, which is referenced from a qrc in `qrc_dir`.
+    pub fn new(source: &Path, alias: &Path, qrc_dir: &Path) -> Result<Self, Errcode> {
+        Ok(Self {
+            alias: qrc_text(alias, source)?,
+            path: qrc_text(&relative_path(qrc_dir, source), source)?,
+            source: source.to_path_buf(),
+            compression: None,
+        })
+    }
+}
+
+/// A `<qresource>` block.
+#[derive(Debug, Clone, Default)]
+pub struct QResource {
+    /// Resource path prefix, e.g. `/icons`.
+    pub prefix: Option<String>,
+    /// Locale the block applies to, e.g. `zh_CN`.
+    pub lang: Option<String>,
+    pub files: Vec<QrcFile>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Qrc {
+    pub resources: Vec<QResource>,
+}
+
+impl Qrc {
+    /// Rejects files that would shadow each other in the resource system,
+    /// e.g. `icons/save.png` in a block without prefix and `save.png` in the
+    /// `/icons` block. Blocks of different languages do not clash.
+    ///
+    /// Names that only differ in case are rejected as well, they cannot be
+    /// checked out side by side on case-insensitive file systems.
+    fn check_aliases(&self) -> Result<(), Errcode> {
+        let mut seen: HashMap<(Option<&str>, String), &QrcFile> = HashMap::new();
+        for resource in &self.resources {
+            for file in &resource.files {
+                let path = resource_path(resource.prefix.as_deref(), &file.alias);
+                let key = (resource.lang.as_deref(), path.to_lowercase());
+                if let Some(first) = seen.insert(key, file) {
+                    return Err(Errcode::GeneralError(GeneralErrorKind::QrcAliasConflict {
+                        alias: path,
+                        first: first.source.clone(),
+                        second: file.source.clone(),
+                    }));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<!DOCTYPE RCC>\n<RCC version=\"1.0\">\n");
+        for resource in &self.resources {
+            xml.push_str("<qresource");
+            if let Some(prefix) = &resource.prefix {
+                xml.push_str(&format!(" prefix=\"{}\"", escape_xml(prefix)));
+            }
+            if let Some(lang) = &resource.lang {
+                xml.push_str(&format!(" lang=\"{}\"", escape_xml(lang)));
+            }
+            xml.push_str(">\n");
+            for file in &resource.files {
+                xml.push_str(&format!("  <file alias=\"{}\"", escape_xml(&file.alias)));
+                if let Some(algorithm) = &file.compression {
+                    xml.push_str(&format!(
+                        " compression-algorithm=\"{}\"",
+                        algorithm.as_str()
+                    ));
+                }
+                xml.push_str(&format!(">{}</file>\n", escape_xml(&file.path)));
+            }
+            xml.push_str("</qresource>\n");
+        }
+        xml.push_str("</RCC>");
+        xml
+    }
+
+    /// Renders, validates and writes the qrc to `qrc_file`.
+    pub fn write(&self, qrc_file: &Path) -> Result<(), Errcode> {
+        self.check_aliases()?;
+
+        let xml = self.to_xml();
+        validate_qrc(qrc_file, &xml)?;
+
+        fs::write(qrc_file, xml).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+                path: qrc_file.to_path_buf(),
+                source: e,
+            })
+        })
+    }
+}
+
+/// Converts a path to the text stored in the qrc.
+fn qrc_text(path: &Path, source: &Path) -> Result<String, Errcode> {
+    let unrepresentable = |reason: &str| {
+        Errcode::GeneralError(GeneralErrorKind::FileNameUnrepresentable {
+            name: source.to_path_buf(),
+            reason: reason.to_string(),
+        })
+    };
+
+    let Some(text) = path.to_str() else {
+        return Err(unrepresentable("not valid UTF-8"));
+    };
+
+    // XML 1.0 cannot carry most control characters, not even escaped
+    if text.chars().any(|c| c.is_control()) {
+        return Err(unrepresentable("contains control characters"));
+    }
+
+    Ok(text.replace('\\', "/"))
+}
+
+/// Parses qrc XML. Qrc files start with `<!DOCTYPE RCC>`, so DTDs are allowed.
+pub fn parse_qrc(xml: &str) -> Result<roxmltree::Document<'_>, roxmltree::Error> {
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    roxmltree::Document::parse_with_options(xml, options)
+}
+
+/// A `<file>` entry of an existing qrc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrcEntry {
+    /// Path inside the resource system, e.g. `/icons/save.png`.
+    pub resource: String,
+    /// The file, relative to the working directory.
+    pub file: PathBuf,
+}
+
+/// Reads every `<file>` entry of an existing qrc.
+pub fn read_qrc_entries(qrc_file: &Path) -> Result<Vec<QrcEntry>, Errcode> {
+    let xml = fs::read_to_string(qrc_file).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+            path: qrc_file.to_path_buf(),
+            source: e,
+        })
+    })?;
+    let doc = parse_qrc(&xml).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::QrcInvalid {
+            path: qrc_file.to_path_buf(),
+            msg: e.to_string(),
+        })
+    })?;
+
+    let qrc_dir = qrc_file.parent().unwrap_or(Path::new(""));
+    let entries = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("file"))
+        .filter_map(|n| {
+            let text = n.text()?.trim();
+            let prefix = n
+                .ancestors()
+                .find(|a| a.has_tag_name("qresource"))
+                .and_then(|a| a.attribute("prefix"));
+            Some(QrcEntry {
+                resource: resource_path(prefix, n.attribute("alias").unwrap_or(text)),
+                file: normalize_path(&qrc_dir.join(text)),
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Returns the files referenced by an existing qrc, relative to the working
+/// directory.
+pub fn referenced_files(qrc_file: &Path) -> Result<Vec<PathBuf>, Errcode> {
+    let mut files: Vec<PathBuf> = read_qrc_entries(qrc_file)?
+        .into_iter()
+        .map(|entry| entry.file)
+        .collect();
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+/// Path of a file inside the resource system, as used in `:/icons/save.png`
+/// without the leading colon.
+pub fn resource_path(prefix: Option<&str>, alias: &str) -> String {
+    let prefix = prefix.unwrap_or("").trim_matches('/');
+    let alias = alias.replace('\\', "/");
+    let alias = alias.trim_start_matches("./").trim_start_matches('/');
+    if prefix.is_empty() {
+        format!("/{}", alias)
+    } else {
+        format!("/{}/{}", prefix, alias)
+    }
+}
+
+/// Resolves `.` and `..` in a resource path like Qt does, `..` above the
+/// root is dropped, e.g. `/themes/../icons/./save.png` becomes `/icons/save.png`.
+pub fn clean_resource_path(resource: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in resource.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            name => components.push(name),
+        }
+    }
+    format!("/{}", components.join("/"))
+}
+
+/// Parses the generated XML back and checks that rcc will accept it.
+fn validate_qrc(qrc_file: &Path, xml: &str) -> Result<(), Errcode> {
+    let invalid = |msg: String| {
+        Errcode::GeneralError(GeneralErrorKind::QrcInvalid {
+            path: qrc_file.to_path_buf(),
+            msg,
+        })
+    };
+
+    let doc = parse_qrc(xml).map_err(|e| invalid(e.to_string()))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "RCC" {
+        return Err(invalid(format!(
+            "unexpected root element <{}>",
+            root.tag_name().name()
+        )));
+    }
+
+    let qrc_dir = qrc_file.parent().unwrap_or(Path::new(""));
+    for resource in root.children().filter(|n| n.is_element()) {
+        if resource.tag_name().name() != "qresource" {
+            return Err(invalid(format!(
+                "unexpected element <{}>",
+                resource.tag_name().name()
+            )));
+        }
+
+        for file in resource.children().filter(|n| n.is_element()) {
+            let path = file.text().unwrap_or_default();
+            if file.tag_name().name() != "file" || path.is_empty() {
+                return Err(invalid("malformed <file> entry".into()));
+            }
+            if !qrc_dir.join(path).is_file() {
+                return Err(invalid(format!(
+                    "referenced file `{}` does not exist",
+                    path
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_qrc_escaping() {
+        let qrc = Qrc {
+            resources: vec![QResource {
+                prefix: Some("/a&b".into()),
+                lang: Some("zh_CN".into()),
+                files: vec![QrcFile {
+                    alias: "Tom & Jerry \"<1>\".png".into(),
+                    path: "../assets/Tom & Jerry \"<1>\".png".into(),
+                    source: "assets/Tom & Jerry \"<1>\".png".into(),
+                    compression: Some(CompressionAlgorithm::None),
+                }],
+            }],
+        };
+
+        let xml = qrc.to_xml();
+        let doc = parse_qrc(&xml).unwrap();
+        let resource = doc
+            .descendants()
+            .find(|n| n.has_tag_name("qresource"))
+            .unwrap();
+        assert_eq!(resource.attribute("prefix"), Some("/a&b"));
+        assert_eq!(resource.attribute("lang"), Some("zh_CN"));
+        let file = doc.descendants().find(|n| n.has_tag_name("file")).unwrap();
+        assert_eq!(file.attribute("alias"), Some("Tom & Jerry \"<1>\".png"));
+        assert_eq!(file.text(), Some("../assets/Tom & Jerry \"<1>\".png"));
+        assert_eq!(file.attribute("compression-algorithm"), Some("none"));
+    }
+
+    #[test]
+    fn test_resource_path() {
+        assert_eq!(resource_path(None, "icons/save.png"), "/icons/save.png");
+        assert_eq!(resource_path(Some("/"), "save.png"), "/save.png");
+        assert_eq!(
+            resource_path(Some("/theme/"), "./dark.qss"),
+            "/theme/dark.qss"
+        );
+    }
+
+    #[test]
+    fn test_qrc_alias_conflict() {
+        let file = |alias: &str| QrcFile {
+            alias: alias.into(),
+            path: format!("../assets/{}", alias),
+            source: format!("assets/{}", alias).into(),
+            compression: None,
+        };
+        let qrc = Qrc {
+            resources: vec![QResource {
+                prefix: None,
+                lang: None,
+                files: vec![file("icons/Save.png"), file("icons/save.png")],
+            }],
+        };
+
+        assert!(matches!(
+            qrc.check_aliases(),
+            Err(Errcode::GeneralError(
+                GeneralErrorKind::QrcAliasConflict { .. }
+            ))
+        ));
+
+        let block = |prefix: Option<&str>, lang: Option<&str>, alias: &str| QResource {
+            prefix: prefix.map(String::from),
+            lang: lang.map(String::from),
+            files: vec![file(alias)],
+        };
+        let qrc = Qrc {
+            resources: vec![
+                block(None, None, "icons/save.png"),
+                block(Some("/icons"), Some("de"), "save.png"),
+            ],
+        };
+        assert!(qrc.check_aliases().is_ok());
+
+        let qrc = Qrc {
+            resources: vec![
+                block(None, None, "icons/save.png"),
+                block(Some("/Icons"), None, "save.png"),
+            ],
+        };
+        assert!(matches!(
+            qrc.check_aliases(),
+            Err(Errcode::GeneralError(
+                GeneralErrorKind::QrcAliasConflict { alias, .. }
+            )) if alias == "/Icons/save.png"
+        ));
+    }
+
+    #[test]
+    fn test_qrc_unrepresentable_name() {
+        let source = Path::new("assets/bad\u{7}name.png");
+        assert!(
+            QrcFile::new(
+                source,
+                Path::new("bad\u{7}name.png"),
+                Path::new("resources")
+            )
+            .is_err()
+        );
+    }
+}
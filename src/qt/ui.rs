@@ -1,26 +1,203 @@
-use std::{fs, path::Path, process::Command};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::Deserialize;
 
 use crate::{
     cache::Cache,
     errcode::{Errcode, GeneralErrorKind, ToolchainErrorKind},
-    files::Files,
+    files::{Files, PatternSet},
+    layout::Layout,
+    qt::{
+        qrc::resource_path,
+        ui_form::UiForm,
+        ui_index::{IndexedForm, write_ui_index},
+        ui_loader::{RuntimeForm, loader_module},
+    },
     run_tool,
 };
 
+/// Placeholder for the `.ui` file stem in [`UiConfig::output`].
+const STEM_PLACEHOLDER: &str = "{stem}";
+
+/// Resource prefix runtime forms are embedded under.
+pub const RUNTIME_FORMS_PREFIX: &str = "/forms";
+
+/// Module generated next to the UI modules with a loader per runtime form.
+const LOADER_MODULE: &str = "forms.py";
+
+/// How the forms of a target get into the application.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiMode {
+    /// Generate Python code with uic.
+    #[default]
+    Compile,
+    /// Embed the `.ui` files into the resources and load them with `QUiLoader`.
+    Runtime,
+}
+
+/// Which forms of a target are loaded at runtime.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeForms {
+    pub mode: UiMode,
+    /// Forms (relative to the UI directory) loaded at runtime in compile mode.
+    pub patterns: PatternSet,
+}
+
+impl RuntimeForms {
+    pub fn contains(&self, rel_path: &Path) -> bool {
+        self.mode == UiMode::Runtime || self.patterns.first_match(rel_path).is_some()
+    }
+}
+
+/// How generated UI modules import their resource modules.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UicImports {
+    /// `import resources_rc`
+    #[default]
+    Default,
+    /// `from . import resources_rc`
+    From,
+    /// Imports relative to the Python paths, e.g. `import app.resources.resources_rc`
+    Absolute,
+}
+
+/// Code generation settings of `[tool.pyside-cli.ui]`.
+#[derive(Debug, Clone)]
+pub struct UiConfig {
+    pub imports: UicImports,
+    /// Import `rc_<name>` instead of `<name>_rc`.
+    pub rc_prefix: bool,
+    /// Use `from PySide6.QtWidgets import *` style imports.
+    pub star_imports: bool,
+    /// File name of the generated module, e.g. `ui_{stem}.py`.
+    pub output: String,
+    /// Emit a `.pyi` stub typing the widgets of every generated module.
+    pub stubs: bool,
+    /// Generate `__init__.py` files re-exporting the `Ui_*` classes of each
+    /// package and a registry of all forms.
+    pub index: bool,
+    /// Forms loaded at runtime, per target.
+    pub runtime: HashMap<String, RuntimeForms>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            imports: UicImports::default(),
+            rc_prefix: false,
+            star_imports: false,
+            output: format!("{}_ui.py", STEM_PLACEHOLDER),
+            stubs: true,
+            index: false,
+            runtime: HashMap::new(),
+        }
+    }
+}
+
+impl UiConfig {
+    /// Checks that the output pattern names a Python module in the output directory.
+    pub fn is_valid_output(pattern: &str) -> bool {
+        pattern.contains(STEM_PLACEHOLDER)
+            && pattern.ends_with(".py")
+            && !pattern.contains(['/', '\\'])
+    }
+
+    pub fn uic_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        match self.imports {
+            UicImports::Default => {}
+            UicImports::From => args.push("--from-imports".to_string()),
+            UicImports::Absolute => args.push("--absolute-imports".to_string()),
+        }
+        if self.rc_prefix {
+            args.push("--rc-prefix".to_string());
+        }
+        if self.star_imports {
+            args.push("--star-imports".to_string());
+        }
+        args
+    }
+
+    pub fn output_name(&self, stem: &str) -> String {
+        self.output.replace(STEM_PLACEHOLDER, stem)
+    }
+
+    /// Everything that affects the generated code, stored in the cache.
+    ///
+    /// Forms moving between the modes regenerate everything, so no form is
+    /// left without its module.
+    fn fingerprint(&self, runtime_forms: &[PathBuf]) -> String {
+        let mut parts = self.uic_args();
+        parts.push(self.output.clone());
+        if self.stubs {
+            parts.push("stubs".to_string());
+        }
+        parts.extend(
+            runtime_forms
+                .iter()
+                .map(|f| format!("runtime:{}", f.to_string_lossy())),
+        );
+        parts.join(" ")
+    }
+}
+
+/// Path of a runtime form inside the resource system, e.g. `/forms/dialogs/settings.ui`.
+pub fn runtime_form_resource(ui_dir: &Path, form: &Path) -> String {
+    let rel_path = form.strip_prefix(ui_dir).unwrap_or(form);
+    resource_path(Some(RUNTIME_FORMS_PREFIX), &rel_path.to_string_lossy())
+}
+
 pub fn convert_ui_files(
     root: &Path,
+    layout: &Layout,
+    config: &UiConfig,
     uic: &Path,
     files: &Files,
     cache: &mut Cache,
 ) -> Result<(), Errcode> {
-    let ui_dir = root.join("ui");
-    let res_dir = root.join("resources");
+    let ui_dir = layout.ui_dir(root);
+    let res_dir = layout.resources_dir(root);
 
     if !ui_dir.exists() || !ui_dir.is_dir() {
         log::info!("No UI files found, skipping.");
         return Ok(());
     }
 
+    write_forms_loader(&ui_dir, &res_dir, &files.runtime_ui_list)?;
+    write_index(&ui_dir, &res_dir, config, &files.ui_list)?;
+
+    if cache.check_ui_options(&config.fingerprint(&files.runtime_ui_list)) {
+        log::info!("uic options changed, regenerating all UI files.");
+    }
+
+    // Modules named after the previous output pattern would shadow the new ones
+    if let Some(previous) = cache.check_ui_output(&config.output) {
+        let previous = UiConfig {
+            output: previous,
+            ..config.clone()
+        };
+        for form in files.ui_list.iter().chain(&files.runtime_ui_list) {
+            let output_file = output_file(&ui_dir, &res_dir, &previous, form)?;
+            remove_stale(&output_file)?;
+            remove_stale(&output_file.with_extension("pyi"))?;
+        }
+    }
+
+    // Modules generated while a form was still compiled, also when all
+    // forms are loaded at runtime
+    for runtime_form in &files.runtime_ui_list {
+        let output_file = output_file(&ui_dir, &res_dir, config, runtime_form)?;
+        remove_stale(&output_file)?;
+        remove_stale(&output_file.with_extension("pyi"))?;
+    }
+
     if files.ui_list.is_empty() {
         log::info!("No UI files found, skipping.");
         return Ok(());
@@ -36,35 +213,20 @@ pub fn convert_ui_files(
     }
 
     for input_file in &files.ui_list {
-        let rel_path = match input_file
-            .parent()
-            .and_then(|p| p.strip_prefix(&ui_dir).ok())
-        {
-            Some(p) => p,
-            None => {
-                return Err(Errcode::GeneralError(GeneralErrorKind::FileNameInvalid {
-                    name: input_file.clone(),
-                }));
-            }
-        };
-
-        let output_dir = res_dir.join(rel_path);
-        fs::create_dir_all(&output_dir).map_err(|e| {
-            Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
-                path: output_dir.clone(),
-                source: e,
-            })
-        })?;
+        let output_file = output_file(&ui_dir, &res_dir, config, input_file)?;
+        if let Some(output_dir) = output_file.parent() {
+            fs::create_dir_all(output_dir).map_err(|e| {
+                Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
+                    path: output_dir.to_path_buf(),
+                    source: e,
+                })
+            })?;
+        }
 
-        let output_file = output_dir.join(format!(
-            "{}_ui.py",
-            input_file
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .ok_or(Errcode::GeneralError(GeneralErrorKind::FileNameInvalid {
-                    name: input_file.clone(),
-                }))?
-        ));
+        // Stubs written before they were disabled would type stale modules
+        if !config.stubs {
+            remove_stale(&output_file.with_extension("pyi"))?;
+        }
 
         let key = input_file.to_string_lossy().to_string();
 
@@ -76,6 +238,7 @@ pub fn convert_ui_files(
         run_tool!(
             &uic,
             Command::new(uic)
+                .args(config.uic_args())
                 .arg(input_file)
                 .arg("-o")
                 .arg(&output_file)
@@ -86,7 +249,203 @@ pub fn convert_ui_files(
             input_file.display(),
             output_file.display()
         );
+
+        if config.stubs {
+            write_ui_stub(input_file, &output_file.with_extension("pyi"))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_stale(path: &Path) -> Result<(), Errcode> {
+    if path.exists() {
+        log::info!("Removing stale {}.", path.display());
+        fs::remove_file(path).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
+                path: path.to_path_buf(),
+                source: e,
+            })
+        })?;
+    }
+    Ok(())
+}
+
+/// Module uic generates from `input_file`, mirroring its place below the UI directory.
+fn output_file(
+    ui_dir: &Path,
+    res_dir: &Path,
+    config: &UiConfig,
+    input_file: &Path,
+) -> Result<PathBuf, Errcode> {
+    let invalid = || {
+        Errcode::GeneralError(GeneralErrorKind::FileNameInvalid {
+            name: input_file.to_path_buf(),
+        })
+    };
+    let rel_path = input_file
+        .parent()
+        .and_then(|p| p.strip_prefix(ui_dir).ok())
+        .ok_or_else(invalid)?;
+    let stem = input_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(invalid)?;
+
+    Ok(res_dir.join(rel_path).join(config.output_name(stem)))
+}
+
+/// Writes the loader module of the runtime forms, or removes it when there are none.
+fn write_forms_loader(ui_dir: &Path, res_dir: &Path, forms: &[PathBuf]) -> Result<(), Errcode> {
+    let loader_file = res_dir.join(LOADER_MODULE);
+    if forms.is_empty() {
+        if loader_file.exists() {
+            log::info!("Removing stale {}.", loader_file.display());
+            fs::remove_file(&loader_file).map_err(|e| {
+                Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
+                    path: loader_file.clone(),
+                    source: e,
+                })
+            })?;
+        }
+        return Ok(());
+    }
+
+    let mut runtime_forms = Vec::new();
+    for form in forms {
+        let rel_path = form.strip_prefix(ui_dir).unwrap_or(form);
+        runtime_forms.push(RuntimeForm {
+            rel_path: rel_path.to_string_lossy().replace('\\', "/"),
+            resource: runtime_form_resource(ui_dir, form),
+            form: read_ui_form(form)?,
+        });
+    }
+
+    fs::create_dir_all(res_dir).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
+            path: res_dir.to_path_buf(),
+            source: e,
+        })
+    })?;
+    let content = loader_module(&runtime_forms);
+    if fs::read_to_string(&loader_file).is_ok_and(|existing| existing == content) {
+        log::info!("{} is up to date.", loader_file.display());
+        return Ok(());
     }
+    fs::write(&loader_file, content).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+            path: loader_file.clone(),
+            source: e,
+        })
+    })?;
+    log::info!(
+        "Wrote loader for {} runtime form(s) to {}.",
+        forms.len(),
+        loader_file.display()
+    );
 
     Ok(())
 }
+
+/// Regenerates the UI index, disabling it resets the files generated before.
+fn write_index(
+    ui_dir: &Path,
+    res_dir: &Path,
+    config: &UiConfig,
+    forms: &[PathBuf],
+) -> Result<(), Errcode> {
+    let mut indexed = Vec::new();
+    if config.index {
+        for form in forms {
+            let output_file = output_file(ui_dir, res_dir, config, form)?;
+            let package = output_file
+                .parent()
+                .and_then(|dir| dir.strip_prefix(res_dir).ok())
+                .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            let rel_path = form.strip_prefix(ui_dir).unwrap_or(form).with_extension("");
+            indexed.push(IndexedForm {
+                name: rel_path.to_string_lossy().replace('\\', "/"),
+                package,
+                module: output_file
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                class: format!("Ui_{}", read_ui_form(form)?.class_name),
+            });
+        }
+        indexed.sort();
+    }
+    write_ui_index(res_dir, &indexed)
+}
+
+fn read_ui_form(ui_file: &Path) -> Result<UiForm, Errcode> {
+    let xml = fs::read_to_string(ui_file).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::ReadFileFailed {
+            path: ui_file.to_path_buf(),
+            source: e,
+        })
+    })?;
+    UiForm::parse(&xml).map_err(|msg| {
+        Errcode::GeneralError(GeneralErrorKind::UiInvalid {
+            path: ui_file.to_path_buf(),
+            msg,
+        })
+    })
+}
+
+/// Writes the type stub of the module generated from `ui_file`.
+fn write_ui_stub(ui_file: &Path, stub_file: &Path) -> Result<(), Errcode> {
+    let form = read_ui_form(ui_file)?;
+
+    let source = ui_file.to_string_lossy().replace('\\', "/");
+    fs::write(stub_file, form.to_stub(&source)).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+            path: stub_file.to_path_buf(),
+            source: e,
+        })
+    })?;
+    log::debug!("Wrote type stub {}.", stub_file.display());
+
+    Ok(())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_ui_config() {
+        let config = UiConfig::default();
+        assert!(config.uic_args().is_empty());
+        assert_eq!(config.output_name("main_window"), "main_window_ui.py");
+
+        let config = UiConfig {
+            imports: UicImports::From,
+            rc_prefix: true,
+            output: "ui_{stem}.py".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.uic_args(), vec!["--from-imports", "--rc-prefix"]);
+        assert_eq!(config.output_name("main_window"), "ui_main_window.py");
+        assert_ne!(
+            config.fingerprint(&[]),
+            UiConfig::default().fingerprint(&[])
+        );
+
+        assert!(UiConfig::is_valid_output("ui_{stem}.py"));
+        assert!(!UiConfig::is_valid_output("forms/{stem}.py"));
+        assert!(!UiConfig::is_valid_output("ui.py"));
+
+        let runtime = RuntimeForms {
+            mode: UiMode::Compile,
+            patterns: PatternSet::new(&["plugins/**".to_string()]).unwrap(),
+        };
+        assert!(runtime.contains(Path::new("plugins/chart.ui")));
+        assert!(!runtime.contains(Path::new("main.ui")));
+        assert_eq!(
+            runtime_form_resource(Path::new("app/ui"), Path::new("app/ui/plugins/chart.ui")),
+            "/forms/plugins/chart.ui"
+        );
+    }
+}
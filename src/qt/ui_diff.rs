@@ -0,0 +1,569 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::qt::ui_form::{UiConnection, UiForm, UiObject, UiProperty};
+
+/// How an object differs between two versions of a form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Class {
+        old: String,
+        new: String,
+    },
+    /// Placed into another parent or layout cell.
+    Moved {
+        from: String,
+        to: String,
+    },
+    /// Same parent, different position among its siblings.
+    Reordered {
+        parent: String,
+    },
+    Property {
+        name: String,
+        /// `<attribute>` instead of `<property>`, e.g. a tab title.
+        attribute: bool,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    Actions {
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+}
+
+/// An object of either form with its changes, unchanged objects are kept
+/// to show where changes are in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDiff {
+    pub name: String,
+    pub class: String,
+    pub parent: Option<String>,
+    pub changes: Vec<Change>,
+}
+
+/// Semantic differences between two versions of a Designer form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UiDiff {
+    pub class_name: Option<(String, String)>,
+    /// Objects of the new form in document order, then removed objects.
+    pub objects: Vec<ObjectDiff>,
+    pub added_connections: Vec<UiConnection>,
+    pub removed_connections: Vec<UiConnection>,
+    /// Promoted widgets, `class` being the class they extend.
+    pub custom_widgets: Vec<ObjectDiff>,
+}
+
+impl UiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.class_name.is_none()
+            && self.objects.iter().all(|o| o.changes.is_empty())
+            && self.added_connections.is_empty()
+            && self.removed_connections.is_empty()
+            && self.custom_widgets.is_empty()
+    }
+}
+
+/// Compares two forms, objects are matched by name.
+///
+/// The top level widgets are always matched, a renamed top level widget
+/// shows up as an `objectName` change.
+pub fn diff_ui_forms(old: &UiForm, new: &UiForm) -> UiDiff {
+    let rename = |name: &str| -> String {
+        if name == old.root.name {
+            new.root.name.clone()
+        } else {
+            name.to_string()
+        }
+    };
+
+    let old_objects: Vec<UiObject> = std::iter::once(&old.root)
+        .chain(&old.objects)
+        .map(|o| UiObject {
+            name: rename(&o.name),
+            parent: o.parent.as_deref().map(rename),
+            ..o.clone()
+        })
+        .collect();
+    let new_objects: Vec<&UiObject> = std::iter::once(&new.root).chain(&new.objects).collect();
+    let old_by_name: HashMap<&str, &UiObject> =
+        old_objects.iter().map(|o| (o.name.as_str(), o)).collect();
+    let new_by_name: HashMap<&str, &UiObject> =
+        new_objects.iter().map(|o| (o.name.as_str(), *o)).collect();
+
+    let reordered = reordered_objects(&old_objects, &new_objects, &new_by_name);
+
+    let mut objects = Vec::new();
+    for object in &new_objects {
+        let mut changes = Vec::new();
+        match old_by_name.get(object.name.as_str()) {
+            None => changes.push(Change::Added),
+            Some(old_object) => {
+                if object.parent.is_none() && old.root.name != new.root.name {
+                    changes.push(Change::Property {
+                        name: "objectName".to_string(),
+                        attribute: false,
+                        old: Some(old.root.name.clone()),
+                        new: Some(new.root.name.clone()),
+                    });
+                }
+                if old_object.class != object.class {
+                    changes.push(Change::Class {
+                        old: old_object.class.clone(),
+                        new: object.class.clone(),
+                    });
+                }
+                if old_object.parent != object.parent || old_object.cell != object.cell {
+                    changes.push(Change::Moved {
+                        from: location(old_object),
+                        to: location(object),
+                    });
+                } else if reordered.contains(object.name.as_str())
+                    && let Some(parent) = &object.parent
+                {
+                    changes.push(Change::Reordered {
+                        parent: parent.clone(),
+                    });
+                }
+                diff_properties(
+                    &old_object.properties,
+                    &object.properties,
+                    false,
+                    &mut changes,
+                );
+                diff_properties(
+                    &old_object.attributes,
+                    &object.attributes,
+                    true,
+                    &mut changes,
+                );
+                if old_object.actions != object.actions {
+                    changes.push(Change::Actions {
+                        old: old_object.actions.clone(),
+                        new: object.actions.clone(),
+                    });
+                }
+            }
+        }
+        objects.push(ObjectDiff {
+            name: object.name.clone(),
+            class: object.class.clone(),
+            parent: object.parent.clone(),
+            changes,
+        });
+    }
+    for object in &old_objects {
+        if !new_by_name.contains_key(object.name.as_str()) {
+            objects.push(ObjectDiff {
+                name: object.name.clone(),
+                class: object.class.clone(),
+                parent: object.parent.clone(),
+                changes: vec![Change::Removed],
+            });
+        }
+    }
+
+    let connection_key = |c: &UiConnection| {
+        (
+            rename(&c.sender),
+            c.signal.clone(),
+            rename(&c.receiver),
+            c.slot.clone(),
+        )
+    };
+    let old_connections: HashSet<_> = old.connections.iter().map(connection_key).collect();
+    let new_connections: HashSet<_> = new.connections.iter().map(connection_key).collect();
+
+    UiDiff {
+        class_name: (old.class_name != new.class_name)
+            .then(|| (old.class_name.clone(), new.class_name.clone())),
+        objects,
+        added_connections: new
+            .connections
+            .iter()
+            .filter(|c| !old_connections.contains(&connection_key(c)))
+            .cloned()
+            .collect(),
+        removed_connections: old
+            .connections
+            .iter()
+            .filter(|c| !new_connections.contains(&connection_key(c)))
+            .cloned()
+            .collect(),
+        custom_widgets: diff_custom_widgets(old, new),
+    }
+}
+
+/// Where an object is placed, e.g. `gridLayout (row 1, column 0)`.
+fn location(object: &UiObject) -> String {
+    let parent = object.parent.as_deref().unwrap_or("top level");
+    match object.cell {
+        Some((row, column)) => format!("{} (row {}, column {})", parent, row, column),
+        None => parent.to_string(),
+    }
+}
+
+fn diff_properties(
+    old: &[UiProperty],
+    new: &[UiProperty],
+    attribute: bool,
+    changes: &mut Vec<Change>,
+) {
+    let value = |properties: &[UiProperty], name: &str| {
+        properties
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.clone())
+    };
+
+    for property in new {
+        let old_value = value(old, &property.name);
+        if old_value.as_ref() != Some(&property.value) {
+            changes.push(Change::Property {
+                name: property.name.clone(),
+                attribute,
+                old: old_value,
+                new: Some(property.value.clone()),
+            });
+        }
+    }
+    for property in old {
+        if value(new, &property.name).is_none() {
+            changes.push(Change::Property {
+                name: property.name.clone(),
+                attribute,
+                old: Some(property.value.clone()),
+                new: None,
+            });
+        }
+    }
+}
+
+/// Objects that kept their parent but changed position among the siblings
+/// outside of grid cells, e.g. in a box layout or a tab widget.
+fn reordered_objects<'a>(
+    old_objects: &[UiObject],
+    new_objects: &[&'a UiObject],
+    new_by_name: &HashMap<&str, &UiObject>,
+) -> HashSet<&'a str> {
+    let mut old_siblings: HashMap<&str, Vec<&str>> = HashMap::new();
+    for object in old_objects {
+        if let Some(parent) = &object.parent
+            && object.cell.is_none()
+            && new_by_name
+                .get(object.name.as_str())
+                .is_some_and(|o| o.parent.as_ref() == Some(parent) && o.cell.is_none())
+        {
+            old_siblings.entry(parent).or_default().push(&object.name);
+        }
+    }
+
+    let mut reordered = HashSet::new();
+    for (parent, old_order) in old_siblings {
+        let new_order: Vec<&str> = new_objects
+            .iter()
+            .filter(|o| o.parent.as_deref() == Some(parent) && old_order.contains(&o.name.as_str()))
+            .map(|o| o.name.as_str())
+            .collect();
+        let kept = longest_common_subsequence(&old_order, &new_order);
+        reordered.extend(
+            new_objects
+                .iter()
+                .map(|o| o.name.as_str())
+                .filter(|name| new_order.contains(name) && !kept.contains(name)),
+        );
+    }
+    reordered
+}
+
+fn longest_common_subsequence<'a>(a: &[&'a str], b: &[&'a str]) -> HashSet<&'a str> {
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut common = HashSet::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            common.insert(a[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+fn diff_custom_widgets(old: &UiForm, new: &UiForm) -> Vec<ObjectDiff> {
+    let mut diffs = Vec::new();
+    for widget in &new.custom_widgets {
+        let mut changes = Vec::new();
+        match old.custom_widgets.iter().find(|w| w.class == widget.class) {
+            None => changes.push(Change::Added),
+            Some(old_widget) => {
+                for (name, old_value, new_value) in [
+                    ("extends", &old_widget.extends, &widget.extends),
+                    ("header", &old_widget.header, &widget.header),
+                ] {
+                    if old_value != new_value {
+                        changes.push(Change::Property {
+                            name: name.to_string(),
+                            attribute: false,
+                            old: old_value.clone(),
+                            new: new_value.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        if !changes.is_empty() {
+            diffs.push(ObjectDiff {
+                name: widget.class.clone(),
+                class: widget.extends.clone().unwrap_or_default(),
+                parent: None,
+                changes,
+            });
+        }
+    }
+    for widget in &old.custom_widgets {
+        if !new.custom_widgets.iter().any(|w| w.class == widget.class) {
+            diffs.push(ObjectDiff {
+                name: widget.class.clone(),
+                class: widget.extends.clone().unwrap_or_default(),
+                parent: None,
+                changes: vec![Change::Removed],
+            });
+        }
+    }
+    diffs
+}
+
+impl fmt::Display for UiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((old, new)) = &self.class_name {
+            writeln!(f, "~ class: {} -> {}", old, new)?;
+        }
+
+        // Show changed objects with the path leading to them
+        let by_name: HashMap<&str, &ObjectDiff> =
+            self.objects.iter().map(|o| (o.name.as_str(), o)).collect();
+        let mut visible = HashSet::new();
+        for object in self.objects.iter().filter(|o| !o.changes.is_empty()) {
+            let mut current = Some(object);
+            while let Some(object) = current
+                && visible.insert(object.name.as_str())
+            {
+                current = object
+                    .parent
+                    .as_deref()
+                    .and_then(|p| by_name.get(p).copied());
+            }
+        }
+        let mut children: HashMap<Option<&str>, Vec<&ObjectDiff>> = HashMap::new();
+        for object in self
+            .objects
+            .iter()
+            .filter(|o| visible.contains(o.name.as_str()))
+        {
+            let parent = object.parent.as_deref().filter(|p| by_name.contains_key(p));
+            children.entry(parent).or_default().push(object);
+        }
+        write_objects(f, &children, None, 0)?;
+
+        if !self.added_connections.is_empty() || !self.removed_connections.is_empty() {
+            writeln!(f, "  connections")?;
+            for (marker, connections) in [
+                ('-', &self.removed_connections),
+                ('+', &self.added_connections),
+            ] {
+                for c in connections {
+                    writeln!(
+                        f,
+                        "{}   {}.{} -> {}.{}",
+                        marker, c.sender, c.signal, c.receiver, c.slot
+                    )?;
+                }
+            }
+        }
+
+        if !self.custom_widgets.is_empty() {
+            writeln!(f, "  custom widgets")?;
+            for widget in &self.custom_widgets {
+                write_object(f, widget, 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_objects(
+    f: &mut fmt::Formatter<'_>,
+    children: &HashMap<Option<&str>, Vec<&ObjectDiff>>,
+    parent: Option<&str>,
+    depth: usize,
+) -> fmt::Result {
+    for object in children.get(&parent).into_iter().flatten() {
+        write_object(f, object, depth)?;
+        write_objects(f, children, Some(&object.name), depth + 1)?;
+    }
+    Ok(())
+}
+
+fn write_object(f: &mut fmt::Formatter<'_>, object: &ObjectDiff, depth: usize) -> fmt::Result {
+    // Markers go first like in a unified diff, details are indented below
+    let indent = "  ".repeat(depth);
+    let marker = match object.changes.first() {
+        Some(Change::Added) => '+',
+        Some(Change::Removed) => '-',
+        Some(_) => '~',
+        None => ' ',
+    };
+    writeln!(f, "{} {}{} ({})", marker, indent, object.name, object.class)?;
+
+    for change in &object.changes {
+        match change {
+            Change::Added | Change::Removed => {}
+            Change::Class { old, new } => writeln!(f, "  {}  class: {} -> {}", indent, old, new)?,
+            Change::Moved { from, to } => writeln!(f, "  {}  moved: {} -> {}", indent, from, to)?,
+            Change::Reordered { parent } => writeln!(f, "  {}  reordered in {}", indent, parent)?,
+            Change::Property {
+                name,
+                attribute,
+                old,
+                new,
+            } => {
+                let name = if *attribute {
+                    format!("{} (attribute)", name)
+                } else {
+                    name.clone()
+                };
+                match (old, new) {
+                    (Some(old), Some(new)) => {
+                        writeln!(f, "  {}  {}: {:?} -> {:?}", indent, name, old, new)?
+                    }
+                    (None, Some(new)) => writeln!(f, "  {}  + {}: {:?}", indent, name, new)?,
+                    (Some(old), None) => writeln!(f, "  {}  - {}: {:?}", indent, name, old)?,
+                    (None, None) => {}
+                }
+            }
+            Change::Actions { old, new } => writeln!(
+                f,
+                "  {}  actions: [{}] -> [{}]",
+                indent,
+                old.join(", "),
+                new.join(", ")
+            )?,
+        }
+    }
+    Ok(())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_diff_ui_forms() {
+        let old = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>Form</class>
+ <widget class="QWidget" name="Form">
+  <layout class="QVBoxLayout" name="layout">
+   <item>
+    <widget class="QLabel" name="title"/>
+   </item>
+   <item>
+    <widget class="QPushButton" name="okButton">
+     <property name="text"><string>OK</string></property>
+    </widget>
+   </item>
+   <item>
+    <widget class="QPushButton" name="cancelButton"/>
+   </item>
+   <item>
+    <widget class="QCheckBox" name="remember"/>
+   </item>
+  </layout>
+ </widget>
+ <connections>
+  <connection>
+   <sender>cancelButton</sender>
+   <signal>clicked()</signal>
+   <receiver>Form</receiver>
+   <slot>close()</slot>
+  </connection>
+ </connections>
+</ui>
+"#;
+        let new = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>Form</class>
+ <widget class="QWidget" name="Form">
+  <layout class="QVBoxLayout" name="layout">
+   <item>
+    <widget class="QLabel" name="title"/>
+   </item>
+   <item>
+    <widget class="QPushButton" name="cancelButton"/>
+   </item>
+   <item>
+    <widget class="QPushButton" name="okButton">
+     <property name="text"><string>Save</string></property>
+     <property name="toolTip"><string>Save the file</string></property>
+    </widget>
+   </item>
+   <item>
+    <widget class="QLineEdit" name="nameEdit"/>
+   </item>
+  </layout>
+ </widget>
+ <connections>
+  <connection>
+   <sender>okButton</sender>
+   <signal>clicked()</signal>
+   <receiver>Form</receiver>
+   <slot>accept()</slot>
+  </connection>
+  <connection>
+   <sender>cancelButton</sender>
+   <signal>clicked()</signal>
+   <receiver>Form</receiver>
+   <slot>close()</slot>
+  </connection>
+ </connections>
+</ui>
+"#;
+        let old = UiForm::parse(old).unwrap();
+        let new = UiForm::parse(new).unwrap();
+        assert!(diff_ui_forms(&old, &old).is_empty());
+
+        let diff = diff_ui_forms(&old, &new);
+        assert_eq!(
+            diff.to_string(),
+            "  Form (QWidget)\n\
+            \x20   layout (QVBoxLayout)\n\
+             ~     okButton (QPushButton)\n\
+            \x20       reordered in layout\n\
+            \x20       text: \"OK\" -> \"Save\"\n\
+            \x20       + toolTip: \"Save the file\"\n\
+             +     nameEdit (QLineEdit)\n\
+             -     remember (QCheckBox)\n\
+            \x20 connections\n\
+             +   okButton.clicked() -> Form.accept()\n"
+        );
+    }
+}
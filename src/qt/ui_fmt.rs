@@ -0,0 +1,297 @@
+use std::fmt::Write;
+
+/// Attributes written first, in this order, all others follow sorted by name.
+const ATTRIBUTE_ORDER: &[&str] = &[
+    "version", "class", "name", "row", "column", "rowspan", "colspan",
+];
+
+/// Compound property values uic reads by tag name, so their children can be sorted.
+const VALUE_ELEMENTS: &[&str] = &[
+    "color",
+    "date",
+    "datetime",
+    "font",
+    "iconset",
+    "point",
+    "pointf",
+    "rect",
+    "rectf",
+    "size",
+    "sizef",
+    "sizepolicy",
+    "time",
+];
+
+/// Order Designer writes the children of [`VALUE_ELEMENTS`] in, unknown
+/// children follow sorted by name.
+const VALUE_CHILD_ORDER: &[&str] = &[
+    "x",
+    "y",
+    "width",
+    "height",
+    "horstretch",
+    "verstretch",
+    "family",
+    "pointsize",
+    "weight",
+    "italic",
+    "bold",
+    "underline",
+    "strikeout",
+    "antialiasing",
+    "stylestrategy",
+    "kerning",
+    "hintingpreference",
+    "fontweight",
+    "normaloff",
+    "normalon",
+    "disabledoff",
+    "disabledon",
+    "activeoff",
+    "activeon",
+    "selectedoff",
+    "selectedon",
+    "hour",
+    "minute",
+    "second",
+    "year",
+    "month",
+    "day",
+    "red",
+    "green",
+    "blue",
+];
+
+/// Elements Designer writes text after the last child for, e.g.
+/// `<iconset><normaloff>:/a.png</normaloff>:/a.png</iconset>`.
+const TRAILING_TEXT_ELEMENTS: &[&str] = &["iconset"];
+
+/// Elements whose text is kept even if it is only whitespace.
+const STRING_ELEMENTS: &[&str] = &["char", "cstring", "string"];
+
+/// Empty elements some Designer versions write and others leave out.
+const OPTIONAL_EMPTY_ELEMENTS: &[&str] = &[
+    "author",
+    "comment",
+    "connections",
+    "exportmacro",
+    "resources",
+];
+
+/// Rewrites a form into a canonical layout.
+///
+/// Only what uic ignores is touched: indentation, attribute order, the order
+/// inside compound values like `<rect>` or `<font>` and empty optional
+/// elements of `<ui>`. Text content is kept as is.
+pub fn format_ui(xml: &str) -> Result<String, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let ui = doc.root_element();
+    if !ui.has_tag_name("ui") {
+        return Err("root element is not <ui>".to_string());
+    }
+    // Text next to elements would be lost by the indentation
+    if let Some(node) = ui.descendants().find(|n| {
+        let allowed = if TRAILING_TEXT_ELEMENTS.contains(&n.tag_name().name()) {
+            leading_nodes(*n).count()
+        } else {
+            n.children().count()
+        };
+        n.is_element()
+            && n.children().any(|c| c.is_element() || c.is_comment())
+            && n.children()
+                .take(allowed)
+                .any(|c| c.is_text() && !c.text().unwrap_or_default().trim().is_empty())
+    }) {
+        return Err(format!(
+            "<{}> on line {} mixes text and elements",
+            node.tag_name().name(),
+            doc.text_pos_at(node.range().start).row
+        ));
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    write_node(&mut out, ui, 0);
+    Ok(out)
+}
+
+fn write_node(out: &mut String, node: roxmltree::Node, depth: usize) {
+    // Designer indents with a single space
+    let indent = " ".repeat(depth);
+
+    if node.is_comment() {
+        writeln!(out, "{}<!--{}-->", indent, node.text().unwrap_or_default()).unwrap();
+        return;
+    }
+
+    let name = node.tag_name().name();
+    write!(out, "{}<{}", indent, name).unwrap();
+    let mut attributes: Vec<_> = node.attributes().collect();
+    attributes.sort_by_key(|a| attribute_rank(a.name()));
+    for attribute in attributes {
+        write!(
+            out,
+            " {}=\"{}\"",
+            attribute.name(),
+            escape(attribute.value(), true)
+        )
+        .unwrap();
+    }
+
+    let mut children: Vec<_> = node
+        .children()
+        .filter(|n| n.is_element() || n.is_comment())
+        .filter(|n| !(node.has_tag_name("ui") && is_optional_empty(*n)))
+        .collect();
+
+    if children.is_empty() {
+        // Text is kept verbatim, whitespace in strings is significant
+        let text = node
+            .text()
+            .filter(|t| STRING_ELEMENTS.contains(&name) || !t.trim().is_empty());
+        match text.filter(|t| !t.is_empty()) {
+            Some(text) => writeln!(out, ">{}</{}>", escape(text, false), name).unwrap(),
+            None => out.push_str("/>\n"),
+        }
+        return;
+    }
+
+    if VALUE_ELEMENTS.contains(&name) {
+        children.sort_by_key(|n| value_child_rank(n.tag_name().name()));
+    }
+    out.push_str(">\n");
+    for child in children {
+        write_node(out, child, depth + 1);
+    }
+
+    // Designer puts the trailing text right before the closing tag
+    let trailing: String = node
+        .children()
+        .skip(leading_nodes(node).count())
+        .filter_map(|n| n.text())
+        .collect();
+    let trailing = trailing.trim();
+    if TRAILING_TEXT_ELEMENTS.contains(&name) && !trailing.is_empty() {
+        out.pop();
+        writeln!(out, "{}</{}>", escape(trailing, false), name).unwrap();
+    } else {
+        writeln!(out, "{}</{}>", indent, name).unwrap();
+    }
+}
+
+/// Children up to and including the last element or comment.
+fn leading_nodes<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    let count = node
+        .children()
+        .enumerate()
+        .filter(|(_, c)| c.is_element() || c.is_comment())
+        .map(|(i, _)| i + 1)
+        .last()
+        .unwrap_or(0);
+    node.children().take(count)
+}
+
+fn attribute_rank(name: &str) -> (usize, &str) {
+    let position = ATTRIBUTE_ORDER.iter().position(|a| *a == name);
+    (position.unwrap_or(ATTRIBUTE_ORDER.len()), name)
+}
+
+fn value_child_rank(name: &str) -> (usize, &str) {
+    let position = VALUE_CHILD_ORDER.iter().position(|a| *a == name);
+    (position.unwrap_or(VALUE_CHILD_ORDER.len()), name)
+}
+
+fn is_optional_empty(node: roxmltree::Node) -> bool {
+    node.is_element()
+        && OPTIONAL_EMPTY_ELEMENTS.contains(&node.tag_name().name())
+        && node.attributes().len() == 0
+        && node
+            .children()
+            .all(|n| n.is_text() && n.text().unwrap_or_default().trim().is_empty())
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\n' if attribute => escaped.push_str("&#10;"),
+            '\t' if attribute => escaped.push_str("&#9;"),
+            '\r' => escaped.push_str("&#13;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_format_ui() {
+        let xml = "<?xml version='1.0'?>\r\n\
+<ui version=\"4.0\">\r\n\
+  <author/>\r\n\
+  <class>Form</class>\r\n\
+  <widget name=\"Form\" class=\"QWidget\">\r\n\
+    <property name=\"geometry\">\r\n\
+      <rect><width>400</width><height>300</height><x>0</x><y>0</y></rect>\r\n\
+    </property>\r\n\
+    <layout name=\"layout\" class=\"QGridLayout\">\r\n\
+      <item column=\"1\" row=\"0\">\r\n\
+        <widget class=\"QLabel\" name=\"label\">\r\n\
+          <property name=\"text\"><string>  Tom &amp; Jerry &lt;3 </string></property>\r\n\
+          <property name=\"icon\">\r\n\
+            <iconset resource=\"../res.qrc\">\r\n\
+              <normaloff>:/icons/save.png</normaloff>:/icons/save.png</iconset>\r\n\
+          </property>\r\n\
+        </widget>\r\n\
+      </item>\r\n\
+    </layout>\r\n\
+  </widget>\r\n\
+  <resources/>\r\n\
+  <connections>\r\n\
+  </connections>\r\n\
+</ui>\r\n";
+
+        let formatted = format_ui(xml).unwrap();
+        assert_eq!(
+            formatted,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>Form</class>
+ <widget class="QWidget" name="Form">
+  <property name="geometry">
+   <rect>
+    <x>0</x>
+    <y>0</y>
+    <width>400</width>
+    <height>300</height>
+   </rect>
+  </property>
+  <layout class="QGridLayout" name="layout">
+   <item row="0" column="1">
+    <widget class="QLabel" name="label">
+     <property name="text">
+      <string>  Tom &amp; Jerry &lt;3 </string>
+     </property>
+     <property name="icon">
+      <iconset resource="../res.qrc">
+       <normaloff>:/icons/save.png</normaloff>:/icons/save.png</iconset>
+     </property>
+    </widget>
+   </item>
+  </layout>
+ </widget>
+</ui>
+"#
+        );
+        assert_eq!(format_ui(&formatted).unwrap(), formatted);
+        assert!(format_ui("<ui><widget>text<layout/></widget></ui>").is_err());
+    }
+}
@@ -0,0 +1,523 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Classes generated by uic that do not live in `PySide6.QtWidgets`.
+const CLASS_MODULES: &[(&str, &str)] = &[
+    ("QAction", "PySide6.QtGui"),
+    ("QActionGroup", "PySide6.QtGui"),
+    ("QChartView", "PySide6.QtCharts"),
+    ("QOpenGLWidget", "PySide6.QtOpenGLWidgets"),
+    ("QPdfView", "PySide6.QtPdfWidgets"),
+    ("QQuickWidget", "PySide6.QtQuickWidgets"),
+    ("QSvgWidget", "PySide6.QtSvgWidgets"),
+    ("QVideoWidget", "PySide6.QtMultimediaWidgets"),
+    ("QWebEngineView", "PySide6.QtWebEngineWidgets"),
+];
+
+/// Designer pseudo classes and the class uic generates for them.
+const CLASS_ALIASES: &[(&str, &str)] = &[("Line", "QFrame")];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiObjectKind {
+    Widget,
+    Layout,
+    Spacer,
+    Action,
+    ActionGroup,
+    ButtonGroup,
+}
+
+/// A named object that becomes an attribute of the generated `Ui_*` class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UiObject {
+    pub kind: UiObjectKind,
+    pub class: String,
+    pub name: String,
+    /// Name of the closest enclosing named object, `None` for the top level widget.
+    pub parent: Option<String>,
+    pub properties: Vec<UiProperty>,
+    /// Values the parent reads from the object, e.g. the `title` of a tab page.
+    pub attributes: Vec<UiProperty>,
+    /// Names from `<addaction>`, the entries of a menu or tool bar.
+    pub actions: Vec<String>,
+    /// `(row, column)` of the layout item holding the object.
+    pub cell: Option<(u32, u32)>,
+    pub line: u32,
+}
+
+impl UiObject {
+    pub fn property(&self, name: &str) -> Option<&UiProperty> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&UiProperty> {
+        self.attributes.iter().find(|p| p.name == name)
+    }
+
+    /// Non-empty value of a string property.
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.property(name)
+            .map(|p| p.value.as_str())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// A `<property>` of an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UiProperty {
+    pub name: String,
+    /// The text of the value, e.g. `Save` for `<string>Save</string>` or
+    /// `0 0 800 600` for a `<rect>`.
+    pub value: String,
+    pub line: u32,
+}
+
+/// A signal/slot `<connection>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UiConnection {
+    pub sender: String,
+    pub signal: String,
+    pub receiver: String,
+    pub slot: String,
+    pub line: u32,
+}
+
+/// A `:/...` resource path used by the form, e.g. in an icon or style sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceReference {
+    /// Path inside the resource system, e.g. `/icons/save.png`.
+    pub resource: String,
+    pub line: u32,
+}
+
+/// A promoted widget from `<customwidgets>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomWidget {
+    pub class: String,
+    pub extends: Option<String>,
+    pub header: Option<String>,
+    pub line: u32,
+}
+
+impl CustomWidget {
+    /// Python module the generated code imports the class from,
+    /// e.g. `widgets/plot.h` becomes `widgets.plot`.
+    pub fn module(&self) -> Option<String> {
+        let header = self.header.as_deref()?.trim();
+        let module = header
+            .strip_suffix(".h")
+            .or_else(|| header.strip_suffix(".hpp"))
+            .unwrap_or(header);
+        Some(module.replace('/', "."))
+    }
+}
+
+/// Python types used by a form, see [`UiForm::types`].
+pub struct FormTypes<'a> {
+    /// Module -> imported classes
+    pub imports: BTreeMap<String, BTreeSet<String>>,
+    pub root_class: String,
+    /// Objects with their class, repeated names left out.
+    pub attributes: Vec<(&'a UiObject, String)>,
+}
+
+/// The parts of a Designer `.ui` file that shape the generated Python code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UiForm {
+    /// Value of `<class>`, the generated class is `Ui_<class>`.
+    pub class_name: String,
+    /// The top level widget, passed to `setupUi`.
+    pub root: UiObject,
+    /// Every named object below the top level widget, in document order.
+    pub objects: Vec<UiObject>,
+    pub custom_widgets: Vec<CustomWidget>,
+    pub connections: Vec<UiConnection>,
+    pub resource_references: Vec<ResourceReference>,
+}
+
+impl UiForm {
+    pub fn parse(xml: &str) -> Result<Self, String> {
+        let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+        let ui = doc.root_element();
+        if !ui.has_tag_name("ui") {
+            return Err("root element is not <ui>".to_string());
+        }
+
+        let line = |node: roxmltree::Node| doc.text_pos_at(node.range().start).row;
+
+        let root_node = ui
+            .children()
+            .find(|n| n.has_tag_name("widget"))
+            .ok_or("missing top level <widget>")?;
+        let properties = |node: roxmltree::Node, tag: &str| -> Vec<UiProperty> {
+            node.children()
+                .filter(|n| n.has_tag_name(tag))
+                .map(|n| UiProperty {
+                    name: n.attribute("name").unwrap_or_default().to_string(),
+                    value: value_text(n),
+                    line: line(n),
+                })
+                .collect()
+        };
+
+        let root = UiObject {
+            kind: UiObjectKind::Widget,
+            class: class_attribute(root_node),
+            name: root_node.attribute("name").unwrap_or_default().to_string(),
+            parent: None,
+            properties: properties(root_node, "property"),
+            attributes: properties(root_node, "attribute"),
+            actions: add_actions(root_node),
+            cell: None,
+            line: line(root_node),
+        };
+
+        let class_name = ui
+            .children()
+            .find(|n| n.has_tag_name("class"))
+            .and_then(|n| n.text())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| root.name.clone());
+
+        let mut objects = Vec::new();
+        let button_groups = ui
+            .children()
+            .filter(|n| n.has_tag_name("buttongroups"))
+            .flat_map(|n| n.children());
+        for node in root_node.descendants().skip(1).chain(button_groups) {
+            let Some((kind, class)) = object_kind(node) else {
+                continue;
+            };
+            let Some(name) = node.attribute("name").filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            let parent = node
+                .ancestors()
+                .skip(1)
+                .find(|n| object_kind(*n).is_some() && n.attribute("name").is_some())
+                .and_then(|n| n.attribute("name"))
+                .map(str::to_string);
+            objects.push(UiObject {
+                kind,
+                class,
+                name: name.to_string(),
+                parent,
+                properties: properties(node, "property"),
+                attributes: properties(node, "attribute"),
+                actions: add_actions(node),
+                cell: layout_cell(node),
+                line: line(node),
+            });
+        }
+
+        let custom_widgets = ui
+            .children()
+            .filter(|n| n.has_tag_name("customwidgets"))
+            .flat_map(|n| n.children())
+            .filter(|n| n.has_tag_name("customwidget"))
+            .map(|node| {
+                let child_text = |tag: &str| {
+                    node.children()
+                        .find(|n| n.has_tag_name(tag))
+                        .and_then(|n| n.text())
+                        .map(|s| s.trim().to_string())
+                };
+                CustomWidget {
+                    class: child_text("class").unwrap_or_default(),
+                    extends: child_text("extends"),
+                    header: child_text("header"),
+                    line: line(node),
+                }
+            })
+            .collect();
+
+        let connections = ui
+            .children()
+            .filter(|n| n.has_tag_name("connections"))
+            .flat_map(|n| n.children())
+            .filter(|n| n.has_tag_name("connection"))
+            .map(|node| {
+                let child_text = |tag: &str| {
+                    node.children()
+                        .find(|n| n.has_tag_name(tag))
+                        .and_then(|n| n.text())
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_default()
+                };
+                UiConnection {
+                    sender: child_text("sender"),
+                    signal: child_text("signal"),
+                    receiver: child_text("receiver"),
+                    slot: child_text("slot"),
+                    line: line(node),
+                }
+            })
+            .collect();
+
+        let resource_references = doc
+            .descendants()
+            .filter(|n| n.is_text())
+            .flat_map(|n| {
+                resource_paths(n.text().unwrap_or_default())
+                    .into_iter()
+                    .map(move |resource| ResourceReference {
+                        resource,
+                        line: line(n),
+                    })
+            })
+            .collect();
+
+        Ok(Self {
+            class_name,
+            root,
+            objects,
+            custom_widgets,
+            connections,
+            resource_references,
+        })
+    }
+
+    /// Renders a `.pyi` stub for the module uic generates from this form.
+    /// Python types of the top level widget and of every object attribute.
+    pub fn types(&self) -> FormTypes<'_> {
+        let mut imports: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut import = |class: &str| {
+            let (module, class) = self.resolve_class(class);
+            imports.entry(module).or_default().insert(class.clone());
+            class
+        };
+
+        let root_class = import(&self.root.class);
+        let mut seen = HashSet::new();
+        let mut attributes = Vec::new();
+        for object in &self.objects {
+            if !seen.insert(object.name.as_str()) {
+                continue;
+            }
+            attributes.push((object, import(&object.class)));
+        }
+
+        FormTypes {
+            imports,
+            root_class,
+            attributes,
+        }
+    }
+
+    pub fn to_stub(&self, source: &str) -> String {
+        let FormTypes {
+            imports,
+            root_class,
+            attributes,
+        } = self.types();
+        let attributes: Vec<String> = attributes
+            .iter()
+            .map(|(object, class)| format!("    {}: {}\n", object.name, class))
+            .collect();
+
+        let mut stub = format!("# Generated by pyside-cli from {}, do not edit.\n", source);
+        for (module, classes) in &imports {
+            let classes: Vec<&str> = classes.iter().map(String::as_str).collect();
+            stub.push_str(&format!("from {} import {}\n", module, classes.join(", ")));
+        }
+
+        let root_name = if self.root.name.is_empty() {
+            "form"
+        } else {
+            self.root.name.as_str()
+        };
+        stub.push_str(&format!("\nclass Ui_{}(object):\n", self.class_name));
+        stub.push_str(&attributes.concat());
+        stub.push_str(&format!(
+            "    def setupUi(self, {0}: {1}) -> None: ...\n    def retranslateUi(self, {0}: {1}) -> None: ...\n",
+            root_name, root_class
+        ));
+        stub
+    }
+
+    /// Qt class a promoted widget is based on, other classes are returned as is.
+    pub fn base_class<'a>(&'a self, class: &'a str) -> &'a str {
+        let mut class = class;
+        for _ in 0..=self.custom_widgets.len() {
+            match self.custom_widgets.iter().find(|w| w.class == class) {
+                Some(custom) => class = custom.extends.as_deref().unwrap_or("QWidget"),
+                None => break,
+            }
+        }
+        class
+    }
+
+    /// Module and class a Designer class is imported as.
+    ///
+    /// A promoted widget without header is typed as the class it extends.
+    fn resolve_class(&self, class: &str) -> (String, String) {
+        let class = python_class(class);
+        match self.custom_widgets.iter().find(|w| w.class == class) {
+            Some(custom) => match custom.module() {
+                Some(module) => (module, class),
+                None => {
+                    let base = python_class(custom.extends.as_deref().unwrap_or("QWidget"));
+                    (class_module(&base).to_string(), base)
+                }
+            },
+            None => (class_module(&class).to_string(), class),
+        }
+    }
+}
+
+fn object_kind(node: roxmltree::Node) -> Option<(UiObjectKind, String)> {
+    let kind = match node.tag_name().name() {
+        "widget" => (UiObjectKind::Widget, class_attribute(node)),
+        "layout" => (UiObjectKind::Layout, class_attribute(node)),
+        "spacer" => (UiObjectKind::Spacer, "QSpacerItem".to_string()),
+        "action" => (UiObjectKind::Action, "QAction".to_string()),
+        "actiongroup" => (UiObjectKind::ActionGroup, "QActionGroup".to_string()),
+        "buttongroup" => (UiObjectKind::ButtonGroup, "QButtonGroup".to_string()),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+fn add_actions(node: roxmltree::Node) -> Vec<String> {
+    node.children()
+        .filter(|n| n.has_tag_name("addaction"))
+        .filter_map(|n| n.attribute("name"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn layout_cell(node: roxmltree::Node) -> Option<(u32, u32)> {
+    let item = node.parent().filter(|n| n.has_tag_name("item"))?;
+    let row = item.attribute("row")?.parse().ok()?;
+    let column = item.attribute("column")?.parse().ok()?;
+    Some((row, column))
+}
+
+/// Joins the text of every element below a `<property>`.
+fn value_text(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds resource paths in a text, either the whole text (`:/icons/save.png`)
+/// or `url(...)` values of a style sheet. `qrc:/` URLs are accepted as well.
+pub fn resource_paths(text: &str) -> Vec<String> {
+    let to_resource = |s: &str| {
+        let s = s.trim().trim_matches(['"', '\'']);
+        s.strip_prefix("qrc:")
+            .or_else(|| s.strip_prefix(':'))
+            .filter(|path| path.starts_with('/'))
+            .map(str::to_string)
+    };
+
+    if let Some(resource) = to_resource(text) {
+        return vec![resource];
+    }
+
+    text.split("url(")
+        .skip(1)
+        .filter_map(|rest| rest.split(')').next())
+        .filter_map(to_resource)
+        .collect()
+}
+
+fn class_attribute(node: roxmltree::Node) -> String {
+    node.attribute("class").unwrap_or("QWidget").to_string()
+}
+
+/// The class uic instantiates for a Designer class name.
+fn python_class(class: &str) -> String {
+    CLASS_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == class)
+        .map_or(class, |(_, actual)| actual)
+        .to_string()
+}
+
+fn class_module(class: &str) -> &'static str {
+    CLASS_MODULES
+        .iter()
+        .find(|(name, _)| *name == class)
+        .map_or("PySide6.QtWidgets", |(_, module)| module)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    const MAIN_WINDOW: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>MainWindow</class>
+ <widget class="QMainWindow" name="MainWindow">
+  <widget class="QWidget" name="centralwidget">
+   <layout class="QVBoxLayout" name="verticalLayout">
+    <item>
+     <widget class="PlotWidget" name="plot"/>
+    </item>
+    <item>
+     <widget class="Line" name="line"/>
+    </item>
+    <item>
+     <spacer name="verticalSpacer"/>
+    </item>
+   </layout>
+  </widget>
+  <action name="actionOpen"/>
+ </widget>
+ <customwidgets>
+  <customwidget>
+   <class>PlotWidget</class>
+   <extends>QWidget</extends>
+   <header>app/widgets/plot.h</header>
+  </customwidget>
+ </customwidgets>
+</ui>
+"#;
+
+    #[test]
+    fn test_parse_ui_form() {
+        let form = UiForm::parse(MAIN_WINDOW).unwrap();
+        assert_eq!(form.class_name, "MainWindow");
+        assert_eq!(form.root.class, "QMainWindow");
+
+        let names: Vec<&str> = form.objects.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "centralwidget",
+                "verticalLayout",
+                "plot",
+                "line",
+                "verticalSpacer",
+                "actionOpen"
+            ]
+        );
+        assert_eq!(form.objects[2].line, 8);
+        assert_eq!(
+            form.custom_widgets[0].module().as_deref(),
+            Some("app.widgets.plot")
+        );
+
+        assert!(UiForm::parse("<ui/>").is_err());
+    }
+
+    #[test]
+    fn test_ui_stub() {
+        let stub = UiForm::parse(MAIN_WINDOW).unwrap().to_stub("main.ui");
+
+        assert!(stub.contains("from PySide6.QtGui import QAction\n"));
+        assert!(stub.contains(
+            "from PySide6.QtWidgets import QFrame, QMainWindow, QSpacerItem, QVBoxLayout, QWidget\n"
+        ));
+        assert!(stub.contains("from app.widgets.plot import PlotWidget\n"));
+        assert!(stub.contains("class Ui_MainWindow(object):\n    centralwidget: QWidget\n"));
+        assert!(stub.contains("    line: QFrame\n"));
+        assert!(stub.contains("    def setupUi(self, MainWindow: QMainWindow) -> None: ...\n"));
+    }
+}
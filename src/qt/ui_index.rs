@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+    errcode::{Errcode, GeneralErrorKind},
+    utils::py_str,
+};
+
+/// First line of every generated index file, other files are never touched
+/// unless empty.
+const HEADER: &str = "# Generated by pyside-cli, do not edit.\n";
+
+/// Module mapping form names to their `Ui_*` classes.
+const REGISTRY_MODULE: &str = "ui_registry.py";
+
+/// A module generated by uic.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IndexedForm {
+    /// Path of the form below the UI directory without `.ui`, e.g. `dialogs/settings`.
+    pub name: String,
+    /// Package directory relative to the resources directory, `""` at the top.
+    pub package: String,
+    /// Module name inside the package, e.g. `settings_ui`.
+    pub module: String,
+    /// e.g. `Ui_Settings`
+    pub class: String,
+}
+
+/// Source of a package `__init__.py` lazily re-exporting `forms`.
+pub fn init_module(forms: &[&IndexedForm]) -> String {
+    let mut module = String::from(HEADER);
+    module.push_str(
+        "from importlib import import_module\n\
+         from typing import TYPE_CHECKING\n\
+         \n\
+         if TYPE_CHECKING:\n",
+    );
+    for form in forms {
+        module.push_str(&format!(
+            "    from .{} import {} as {}\n",
+            form.module, form.class, form.class
+        ));
+    }
+
+    module.push_str("\n# Class -> module, imported on first access\n_EXPORTS = {\n");
+    for form in forms {
+        module.push_str(&format!(
+            "    {}: {},\n",
+            py_str(&form.class),
+            py_str(&format!(".{}", form.module))
+        ));
+    }
+    module.push_str("}\n\n__all__ = [\n");
+    for form in forms {
+        module.push_str(&format!("    {},\n", py_str(&form.class)));
+    }
+    module.push_str(
+        "]\n\
+         \n\
+         \n\
+         def __getattr__(name):\n\
+         \x20   if name not in _EXPORTS:\n\
+         \x20       raise AttributeError(f\"module {__name__!r} has no attribute {name!r}\")\n\
+         \x20   return getattr(import_module(_EXPORTS[name], __name__), name)\n",
+    );
+    module
+}
+
+/// Source of the registry mapping every form name to its `Ui_*` class.
+pub fn registry_module(forms: &[IndexedForm]) -> String {
+    let mut module = String::from(HEADER);
+    module.push_str(
+        "\"\"\"Generated UI classes by form name, e.g. `dialogs/settings`.\"\"\"\n\
+         from importlib import import_module\n\
+         \n\
+         # Form name -> (module, class)\n\
+         FORMS = {\n",
+    );
+    for form in forms {
+        let mut path = form.package.replace('/', ".");
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(&form.module);
+        module.push_str(&format!(
+            "    {}: ({}, {}),\n",
+            py_str(&form.name),
+            py_str(&format!(".{}", path)),
+            py_str(&form.class)
+        ));
+    }
+    module.push_str(
+        "}\n\
+         \n\
+         \n\
+         def get(name):\n\
+         \x20   \"\"\"Return the `Ui_*` class generated from form `name`.\"\"\"\n\
+         \x20   module, cls = FORMS[name]\n\
+         \x20   return getattr(import_module(module, __package__), cls)\n",
+    );
+    module
+}
+
+/// Regenerates the `__init__.py` files and the registry of the resources
+/// directory, or resets them when `forms` is empty.
+pub fn write_ui_index(res_dir: &Path, forms: &[IndexedForm]) -> Result<(), Errcode> {
+    let mut packages: BTreeMap<&str, Vec<&IndexedForm>> = BTreeMap::new();
+    for form in forms {
+        let exports = packages.entry(&form.package).or_default();
+        if let Some(first) = exports.iter().find(|f| f.class == form.class) {
+            log::warn!(
+                "{} and {} both define {}, only the first is re-exported.",
+                first.name,
+                form.name,
+                form.class
+            );
+            continue;
+        }
+        exports.push(form);
+    }
+
+    // Packages that lost all their forms are reset as well
+    let mut init_files: BTreeSet<PathBuf> = packages
+        .keys()
+        .map(|package| res_dir.join(package).join("__init__.py"))
+        .collect();
+    if res_dir.is_dir() {
+        init_files.extend(
+            WalkDir::new(res_dir)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.file_name() == "__init__.py" && is_generated(e.path()))
+                .map(|e| e.into_path()),
+        );
+    }
+
+    for init_file in init_files {
+        let package = init_file
+            .parent()
+            .and_then(|dir| dir.strip_prefix(res_dir).ok())
+            .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        if is_user_owned(&init_file) {
+            log::warn!(
+                "{} was not generated by pyside-cli, its forms are not re-exported.",
+                init_file.display()
+            );
+            continue;
+        }
+        let content = match packages.get(package.as_str()) {
+            Some(exports) => init_module(exports),
+            None => String::new(),
+        };
+        write_if_changed(&init_file, &content)?;
+    }
+
+    let registry_file = res_dir.join(REGISTRY_MODULE);
+    if forms.is_empty() {
+        if registry_file.exists() && is_generated(&registry_file) {
+            log::info!("Removing stale {}.", registry_file.display());
+            fs::remove_file(&registry_file).map_err(|e| {
+                Errcode::GeneralError(GeneralErrorKind::RemoveFileFailed {
+                    path: registry_file.clone(),
+                    source: e,
+                })
+            })?;
+        }
+    } else if is_user_owned(&registry_file) {
+        log::warn!(
+            "{} was not generated by pyside-cli, skipped.",
+            registry_file.display()
+        );
+    } else {
+        write_if_changed(&registry_file, &registry_module(forms))?;
+    }
+
+    Ok(())
+}
+
+fn is_generated(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.starts_with(HEADER))
+}
+
+/// Files with content of their own, empty ones are taken over.
+fn is_user_owned(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .is_ok_and(|content| !content.is_empty() && !content.starts_with(HEADER))
+}
+
+/// Keeps files untouched when their content is the same, so that their
+/// modification time stays.
+fn write_if_changed(path: &Path, content: &str) -> Result<(), Errcode> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| {
+            Errcode::GeneralError(GeneralErrorKind::CreateFileFailed {
+                path: dir.to_path_buf(),
+                source: e,
+            })
+        })?;
+    }
+    fs::write(path, content).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    })?;
+    log::info!("Updated UI index {}.", path.display());
+    Ok(())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_ui_index_modules() {
+        let forms = vec![
+            IndexedForm {
+                name: "dialogs/settings".to_string(),
+                package: "dialogs".to_string(),
+                module: "settings_ui".to_string(),
+                class: "Ui_Settings".to_string(),
+            },
+            IndexedForm {
+                name: "main".to_string(),
+                package: String::new(),
+                module: "main_ui".to_string(),
+                class: "Ui_MainWindow".to_string(),
+            },
+        ];
+
+        let init = init_module(&[&forms[0]]);
+        assert!(init.starts_with(HEADER));
+        assert!(init.contains("    from .settings_ui import Ui_Settings as Ui_Settings\n"));
+        assert!(init.contains("_EXPORTS = {\n    'Ui_Settings': '.settings_ui',\n}\n"));
+
+        let registry = registry_module(&forms);
+        assert!(registry.contains(
+            "FORMS = {\n\
+             \x20   'dialogs/settings': ('.dialogs.settings_ui', 'Ui_Settings'),\n\
+             \x20   'main': ('.main_ui', 'Ui_MainWindow'),\n\
+             }\n"
+        ));
+    }
+
+    #[test]
+    fn test_user_owned_init_untouched() {
+        let res_dir =
+            std::env::temp_dir().join(format!("pyside-cli-ui-index-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&res_dir);
+        fs::create_dir_all(res_dir.join("dialogs")).unwrap();
+        let own_init = "from .theme import apply_theme\n";
+        fs::write(res_dir.join("__init__.py"), own_init).unwrap();
+        fs::write(res_dir.join("dialogs/__init__.py"), "").unwrap();
+
+        let forms = vec![
+            IndexedForm {
+                name: "main".to_string(),
+                package: String::new(),
+                module: "main_ui".to_string(),
+                class: "Ui_MainWindow".to_string(),
+            },
+            IndexedForm {
+                name: "dialogs/settings".to_string(),
+                package: "dialogs".to_string(),
+                module: "settings_ui".to_string(),
+                class: "Ui_Settings".to_string(),
+            },
+        ];
+        write_ui_index(&res_dir, &forms).unwrap();
+
+        let read = |path: &str| fs::read_to_string(res_dir.join(path)).unwrap();
+        assert_eq!(read("__init__.py"), own_init);
+        assert!(read("dialogs/__init__.py").starts_with(HEADER));
+        assert!(read(REGISTRY_MODULE).starts_with(HEADER));
+
+        write_ui_index(&res_dir, &[]).unwrap();
+        assert_eq!(read("__init__.py"), own_init);
+        assert_eq!(read("dialogs/__init__.py"), "");
+        fs::remove_dir_all(&res_dir).unwrap();
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    qt::ui_form::{FormTypes, UiForm, UiObjectKind},
+    utils::py_str,
+};
+
+/// A form embedded into the resources and loaded with `QUiLoader`.
+pub struct RuntimeForm {
+    /// Path relative to the UI directory, e.g. `dialogs/settings.ui`.
+    pub rel_path: String,
+    /// Path inside the resource system, e.g. `/forms/dialogs/settings.ui`.
+    pub resource: String,
+    pub form: UiForm,
+}
+
+impl RuntimeForm {
+    /// `dialogs/settings.ui` becomes `dialogs_settings`.
+    fn identifier(&self) -> String {
+        let stem = self.rel_path.strip_suffix(".ui").unwrap_or(&self.rel_path);
+        let mut identifier: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+            identifier.insert(0, '_');
+        }
+        identifier
+    }
+
+    fn function_name(&self) -> String {
+        format!("load_{}", self.identifier())
+    }
+
+    /// `dialogs_settings` becomes `DialogsSettingsForm`.
+    fn type_name(&self) -> String {
+        let mut name: String = self
+            .identifier()
+            .split('_')
+            .map(|part| {
+                let mut chars = part.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            })
+            .collect();
+        name.push_str("Form");
+        name
+    }
+}
+
+/// Source of the module with one typed `load_*` function per form.
+///
+/// The widget types only exist for type checkers, at runtime PySide sets
+/// the named children of a loaded form as attributes.
+pub fn loader_module(forms: &[RuntimeForm]) -> String {
+    let mut imports: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut classes = String::new();
+    let mut functions = String::new();
+
+    // `dialogs/settings.ui` and `dialogs_settings.ui` share their names
+    let mut loaded: BTreeMap<String, &RuntimeForm> = BTreeMap::new();
+    let mut generated = Vec::new();
+    for form in forms {
+        if let Some(first) = loaded.get(&form.identifier()) {
+            log::warn!(
+                "{} and {} both get the loader {}, only the first is generated.",
+                first.rel_path,
+                form.rel_path,
+                form.function_name()
+            );
+            continue;
+        }
+        loaded.insert(form.identifier(), form);
+        generated.push(form);
+
+        let FormTypes {
+            imports: form_imports,
+            root_class,
+            attributes,
+        } = form.form.types();
+        for (module, names) in form_imports {
+            imports.entry(module).or_default().extend(names);
+        }
+
+        let type_name = form.type_name();
+        classes.push_str(&format!("\n    class {}({}):\n", type_name, root_class));
+        // Spacers are no QObjects and are not set on the loaded form
+        let attributes: Vec<_> = attributes
+            .iter()
+            .filter(|(object, _)| object.kind != UiObjectKind::Spacer)
+            .collect();
+        if attributes.is_empty() {
+            classes.push_str("        pass\n");
+        }
+        for (object, class) in attributes {
+            classes.push_str(&format!("        {}: {}\n", object.name, class));
+        }
+
+        // Promoted widgets have to be registered with the loader
+        let mut custom_imports = String::new();
+        let mut custom_classes = Vec::new();
+        for custom in &form.form.custom_widgets {
+            if let Some(module) = custom.module() {
+                custom_imports.push_str(&format!("    from {} import {}\n", module, custom.class));
+                custom_classes.push(format!("{},", custom.class));
+            }
+        }
+        let custom_argument = if custom_classes.is_empty() {
+            String::new()
+        } else {
+            custom_imports.push('\n');
+            format!(", ({})", custom_classes.join(" "))
+        };
+
+        functions.push_str(&format!(
+            "\n\ndef {function}(parent: Optional[QWidget] = None) -> {type_name}:\n\
+             \x20   \"\"\"Load `{rel_path}` from `:{resource}`.\"\"\"\n\
+             {custom_imports}\
+             \x20   return cast({type_literal}, _load({path}, parent{custom_argument}))\n",
+            function = form.function_name(),
+            type_name = type_name,
+            rel_path = form.rel_path,
+            resource = form.resource,
+            type_literal = py_str(&type_name),
+            path = py_str(&format!(":{}", form.resource)),
+        ));
+    }
+
+    let mut module = String::from(
+        "# Generated by pyside-cli, do not edit.\n\
+         \"\"\"Loaders for the forms embedded into the resources.\n\
+         \n\
+         The resource module has to be imported before a form is loaded.\n\
+         \"\"\"\n\
+         from __future__ import annotations\n\
+         \n\
+         from typing import TYPE_CHECKING, Optional, Sequence, cast\n\
+         \n\
+         from PySide6.QtCore import QFile, QIODevice\n\
+         from PySide6.QtUiTools import QUiLoader\n\
+         from PySide6.QtWidgets import QWidget\n\
+         \n\
+         if TYPE_CHECKING:\n",
+    );
+    for (module_name, names) in &imports {
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        module.push_str(&format!(
+            "    from {} import {}\n",
+            module_name,
+            names.join(", ")
+        ));
+    }
+    module.push_str(&classes);
+    module.push_str(
+        "\n\n\
+         def _load(\n\
+         \x20   path: str, parent: Optional[QWidget], custom_widgets: Sequence[type] = ()\n\
+         ) -> QWidget:\n\
+         \x20   loader = QUiLoader()\n\
+         \x20   for widget_class in custom_widgets:\n\
+         \x20       loader.registerCustomWidget(widget_class)\n\
+         \x20   file = QFile(path)\n\
+         \x20   if not file.open(QIODevice.OpenModeFlag.ReadOnly):\n\
+         \x20       raise RuntimeError(f\"Cannot open {path}: {file.errorString()}\")\n\
+         \x20   try:\n\
+         \x20       widget = loader.load(file, parent)\n\
+         \x20   finally:\n\
+         \x20       file.close()\n\
+         \x20   if widget is None:\n\
+         \x20       raise RuntimeError(f\"Cannot load {path}: {loader.errorString()}\")\n\
+         \x20   return widget\n",
+    );
+    module.push_str(&functions);
+
+    module.push_str("\n\n# Form path -> loader\nFORMS = {\n");
+    for form in generated {
+        module.push_str(&format!(
+            "    {}: {},\n",
+            py_str(&form.rel_path),
+            form.function_name()
+        ));
+    }
+    module.push_str("}\n");
+    module
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_loader_module() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>Settings</class>
+ <widget class="QDialog" name="Settings">
+  <layout class="QVBoxLayout" name="layout">
+   <item>
+    <widget class="QCheckBox" name="darkMode"/>
+   </item>
+   <item>
+    <spacer name="spacer"/>
+   </item>
+   <item>
+    <widget class="PlotWidget" name="plot"/>
+   </item>
+  </layout>
+ </widget>
+ <customwidgets>
+  <customwidget>
+   <class>PlotWidget</class>
+   <extends>QWidget</extends>
+   <header>app/widgets/plot.h</header>
+  </customwidget>
+ </customwidgets>
+</ui>
+"#;
+        let forms = [
+            RuntimeForm {
+                rel_path: "dialogs/settings.ui".to_string(),
+                resource: "/forms/dialogs/settings.ui".to_string(),
+                form: UiForm::parse(xml).unwrap(),
+            },
+            RuntimeForm {
+                rel_path: "dialogs_settings.ui".to_string(),
+                resource: "/forms/dialogs_settings.ui".to_string(),
+                form: UiForm::parse(xml).unwrap(),
+            },
+        ];
+
+        let module = loader_module(&forms);
+        assert!(module.contains(
+            "    from PySide6.QtWidgets import QCheckBox, QDialog, QSpacerItem, QVBoxLayout\n"
+        ));
+        assert_eq!(module.matches("class DialogsSettingsForm").count(), 1);
+        assert!(module.contains(
+            "    class DialogsSettingsForm(QDialog):\n\
+             \x20       layout: QVBoxLayout\n\
+             \x20       darkMode: QCheckBox\n\
+             \x20       plot: PlotWidget\n"
+        ));
+        assert!(module.contains(
+            "def load_dialogs_settings(parent: Optional[QWidget] = None) -> DialogsSettingsForm:\n\
+             \x20   \"\"\"Load `dialogs/settings.ui` from `:/forms/dialogs/settings.ui`.\"\"\"\n\
+             \x20   from app.widgets.plot import PlotWidget\n\
+             \n\
+             \x20   return cast('DialogsSettingsForm', _load(':/forms/dialogs/settings.ui', parent, (PlotWidget,)))\n"
+        ));
+        assert!(
+            module.ends_with("FORMS = {\n    'dialogs/settings.ui': load_dialogs_settings,\n}\n")
+        );
+    }
+}
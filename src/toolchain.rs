@@ -1,7 +1,7 @@
 use std::{env, path::PathBuf, process::Command};
 
 use serde_json;
-use which::which;
+use which::{which, which_in};
 
 /// Check if the current Python interpreter is running in a virtual environment.
 fn in_virtual_env() -> bool {
@@ -40,8 +40,9 @@ fn python_site_packages() -> Vec<PathBuf> {
         .collect()
 }
 
-/// Append PySide6 directory to PATH (process-local)
-fn add_pyside6_to_path() {
+/// Append PySide6 directory to PATH (process-local), returns the appended
+/// directories
+fn add_pyside6_to_path() -> Vec<PathBuf> {
     let site_packages = python_site_packages();
 
     let mut appended = Vec::new();
@@ -49,24 +50,40 @@ fn add_pyside6_to_path() {
     for site in site_packages {
         let pyside = site.join("PySide6");
         if pyside.exists() && pyside.is_dir() {
+            // Qt's own tools (e.g. rcc) live here on Linux and macOS
+            let libexec = pyside.join("Qt").join("libexec");
             appended.push(pyside);
+            if libexec.is_dir() {
+                appended.push(libexec);
+            }
         }
     }
 
     if appended.is_empty() {
-        return;
+        return appended;
     }
 
     let old = env::var_os("PATH").unwrap_or_default();
     let mut paths: Vec<PathBuf> = env::split_paths(&old).collect();
 
-    paths.extend(appended);
+    paths.extend(appended.iter().cloned());
 
     if let Ok(new_path) = env::join_paths(paths) {
         unsafe {
             env::set_var("PATH", new_path);
         }
     }
+    appended
+}
+
+/// Qt's rcc shipped with PySide6, a system rcc (e.g. from Qt 5) earlier on
+/// PATH would write bundles for the wrong Qt version.
+fn find_qt_rcc(pyside_dirs: &[PathBuf]) -> Option<PathBuf> {
+    let bundled = env::join_paths(pyside_dirs)
+        .ok()
+        .zip(env::current_dir().ok())
+        .and_then(|(paths, cwd)| which_in("rcc", Some(paths), cwd).ok());
+    bundled.or_else(|| which("rcc").ok())
 }
 
 #[derive(Debug)]
@@ -74,6 +91,7 @@ pub struct Toolchain {
     pub git: Option<PathBuf>,
     pub uic: Option<PathBuf>,
     pub rcc: Option<PathBuf>,
+    pub qt_rcc: Option<PathBuf>,
     pub lupdate: Option<PathBuf>,
     pub lrelease: Option<PathBuf>,
     pub nuitka: Option<PathBuf>,
@@ -86,12 +104,13 @@ impl Toolchain {
         if !in_virtual_env() {
             log::warn!("Not running in a virtual environment, missing tools may not be found.");
         }
-        add_pyside6_to_path();
+        let pyside_dirs = add_pyside6_to_path();
 
         Self {
             git: which("git").ok(),
             uic: which("pyside6-uic").ok(),
             rcc: which("pyside6-rcc").ok(),
+            qt_rcc: find_qt_rcc(&pyside_dirs),
             lupdate: which("lupdate").ok(),
             lrelease: which("lrelease").ok(),
             nuitka: which("nuitka").ok(),
@@ -1,6 +1,6 @@
 use std::{
     fs,
-    path::Path,
+    path::{Component, Path, PathBuf},
     time::{Duration, UNIX_EPOCH},
 };
 
@@ -47,6 +47,139 @@ pub fn format_duration(d: Duration) -> String {
     }
 }
 
+/// Formats a size in bytes, e.g. `512 B`, `12.3 KiB` or `4.0 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Returns the value of `SOURCE_DATE_EPOCH`, if set to a valid timestamp.
+///
+/// Ref: https://reproducible-builds.org/specs/source-date-epoch/
+pub fn source_date_epoch() -> Option<u64> {
+    let value = std::env::var("SOURCE_DATE_EPOCH").ok()?;
+    match value.trim().parse::<u64>() {
+        Ok(epoch) => Some(epoch),
+        Err(_) => {
+            log::warn!("Ignoring invalid SOURCE_DATE_EPOCH `{}`.", value);
+            None
+        }
+    }
+}
+
+/// Formats a Unix timestamp as an ISO 8601 UTC date, e.g. `2024-05-01T12:00:00Z`.
+pub fn format_utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // Civil-from-days, ref: https://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Escapes text for use in XML content and attribute values.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quotes `s` as a Python string literal.
+pub fn py_str(s: &str) -> String {
+    format!(
+        "'{}'",
+        s.replace('\\', "\\\\")
+            .replace('\'', "\\'")
+            .replace('\n', "\\n")
+    )
+}
+
+/// Lexically resolves `.` and `..` components, e.g. `app/icons/../assets/a.png`
+/// becomes `app/assets/a.png`. Leading `..` components are kept.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(result.components().next_back(), Some(Component::Normal(_))) {
+                    result.pop();
+                } else {
+                    result.push("..");
+                }
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Returns `path` expressed relative to the directory `base`.
+///
+/// Both paths must be relative to the same root (or both absolute),
+/// e.g. `app/resources` and `app/assets/icon.png` give `../assets/icon.png`.
+pub fn relative_path(base: &Path, path: &Path) -> PathBuf {
+    let base: Vec<Component> = base
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect();
+    let path: Vec<Component> = path
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect();
+
+    let common = base
+        .iter()
+        .zip(path.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut result = PathBuf::new();
+    for _ in common..base.len() {
+        result.push("..");
+    }
+    for component in &path[common..] {
+        result.push(component);
+    }
+    result
+}
+
 #[macro_export]
 macro_rules! run_tool {
     ($name:expr, $cmd:expr) => {{
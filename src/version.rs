@@ -0,0 +1,529 @@
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    errcode::{Errcode, GeneralErrorKind},
+    utils::{format_utc_timestamp, py_str, source_date_epoch},
+};
+
+/// Version used when no version can be found.
+pub const DEFAULT_VERSION: &str = "0.0.0.0";
+
+/// Line of `version.py` that changes on every build, ignored when deciding
+/// whether the file needs to be rewritten.
+const BUILD_DATE_KEY: &str = "__build_date__";
+
+/// Where the application version comes from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionSource {
+    /// The nearest matching git tag.
+    #[default]
+    Git,
+    /// `version` of the `[project]` table.
+    Project,
+    /// An environment variable, e.g. set by CI.
+    Env,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VersionConfig {
+    pub source: VersionSource,
+    /// Glob passed to `git describe --match`, e.g. `v*`.
+    pub tag_match: Option<String>,
+    /// Tags must also match this regex. Its first capture group, if any,
+    /// is used as the version, e.g. `^release-(.+)$`.
+    pub tag_regex: Option<Regex>,
+    /// Removed from the tag, e.g. `v` turns `v1.2.3` into `1.2.3`.
+    pub strip_prefix: Option<String>,
+    /// Variable read by [`VersionSource::Env`].
+    pub env: Option<String>,
+    pub project_version: Option<String>,
+    /// Version used when the source has none, [`DEFAULT_VERSION`] unless set.
+    pub fallback: Option<String>,
+}
+
+impl VersionConfig {
+    pub fn fallback_version(&self) -> String {
+        self.fallback
+            .clone()
+            .unwrap_or_else(|| DEFAULT_VERSION.to_string())
+    }
+
+    /// Turns a matching tag into a version, e.g. `v1.2.3` into `1.2.3`.
+    fn version_from_tag(&self, tag: &str) -> String {
+        let version = match &self.tag_regex {
+            Some(regex) => regex
+                .captures(tag)
+                .and_then(|caps| caps.get(1))
+                .map_or(tag, |m| m.as_str()),
+            None => tag,
+        };
+
+        match &self.strip_prefix {
+            Some(prefix) => version.strip_prefix(prefix.as_str()).unwrap_or(version),
+            None => version,
+        }
+        .to_string()
+    }
+
+    /// `git describe` arguments restricting which tags are considered.
+    fn describe_filters(&self, git: &Path) -> Vec<String> {
+        let Some(regex) = &self.tag_regex else {
+            return self
+                .tag_match
+                .iter()
+                .map(|glob| format!("--match={}", glob))
+                .collect();
+        };
+
+        // git only understands globs, so pick the newest matching tag reachable
+        // from HEAD and describe relative to it alone
+        let mut args = vec![
+            "tag".to_string(),
+            "--merged=HEAD".to_string(),
+            "--sort=-creatordate".to_string(),
+            "--list".to_string(),
+        ];
+        args.extend(self.tag_match.iter().cloned());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let tags = git_output(git, &args).unwrap_or_default();
+
+        match tags.lines().find(|tag| regex.is_match(tag)) {
+            // Tag names cannot contain glob characters, the tag matches itself only
+            Some(tag) => vec![format!("--match={}", tag)],
+            None => vec!["--exclude=*".to_string()],
+        }
+    }
+}
+
+/// Version details of the working tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    pub source: VersionSource,
+    /// The version from the configured source, or the fallback.
+    pub version: String,
+    /// Output of `git describe --long --dirty`, e.g. `v1.2.0-3-gabc1234-dirty`.
+    pub describe: String,
+    /// Number of commits since the last matching tag.
+    pub distance: u32,
+    /// Abbreviated hash of HEAD.
+    pub commit: String,
+    pub dirty: bool,
+    /// Committer date of HEAD in strict ISO 8601.
+    pub commit_date: String,
+}
+
+impl VersionInfo {
+    /// Resolves the version from the configured source and collects the
+    /// git details of the working tree.
+    ///
+    /// Without git, or outside a repository, the git fields stay empty,
+    /// so that a version module can still be generated.
+    pub fn resolve(config: &VersionConfig, git: Option<&Path>) -> Result<Self, Errcode> {
+        let mut info = match git {
+            Some(git) => Self::from_git(config, git),
+            None => Self {
+                source: VersionSource::Git,
+                version: config.fallback_version(),
+                describe: String::new(),
+                distance: 0,
+                commit: String::new(),
+                dirty: false,
+                commit_date: String::new(),
+            },
+        };
+
+        info.source = config.source.clone();
+        match config.source {
+            VersionSource::Git => {}
+            VersionSource::Project => {
+                info.version = config
+                    .project_version
+                    .clone()
+                    .unwrap_or_else(|| config.fallback_version());
+            }
+            VersionSource::Env => {
+                let name = config.env.clone().unwrap_or_default();
+                info.version = match (std::env::var(&name), &config.fallback) {
+                    (Ok(value), _) if !value.trim().is_empty() => value.trim().to_string(),
+                    (_, Some(fallback)) => {
+                        log::warn!("{} is not set, using fallback version {}.", name, fallback);
+                        fallback.clone()
+                    }
+                    _ => {
+                        return Err(Errcode::GeneralError(GeneralErrorKind::VersionEnvNotSet {
+                            name,
+                        }));
+                    }
+                };
+            }
+        }
+
+        Ok(info)
+    }
+
+    fn from_git(config: &VersionConfig, git: &Path) -> Self {
+        let mut args = vec![
+            "describe".to_string(),
+            "--tags".to_string(),
+            "--long".to_string(),
+            "--dirty".to_string(),
+            "--always".to_string(),
+            "--first-parent".to_string(),
+            "--abbrev=7".to_string(),
+        ];
+        args.extend(config.describe_filters(git));
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let describe = git_output(git, &args).unwrap_or_default();
+
+        let (tag, distance, commit, dirty) = parse_describe(&describe);
+        let distance = match tag {
+            Some(_) => distance,
+            // Without a tag every commit counts
+            None => git_output(git, &["rev-list", "--count", "--first-parent", "HEAD"])
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(0),
+        };
+
+        Self {
+            source: VersionSource::Git,
+            version: match tag {
+                Some(tag) => config.version_from_tag(tag),
+                None => config.fallback_version(),
+            },
+            describe: describe.clone(),
+            distance,
+            commit: commit.to_string(),
+            dirty,
+            commit_date: git_output(git, &["log", "-1", "--format=%cI"]).unwrap_or_default(),
+        }
+    }
+
+    /// The version normalized according to PEP 440.
+    ///
+    /// For git versions, commits after the tag become a `.postN` release and
+    /// the commit hash and dirty state go into the local segment,
+    /// e.g. `1.2.0.post3+gabc1234.dirty`. Other sources are used as they are.
+    pub fn pep440(&self) -> String {
+        let mut version = normalize_pep440(&self.version).unwrap_or_else(|| {
+            log::warn!(
+                "Version `{}` is not PEP 440 compliant, using 0.0.0 instead.",
+                self.version
+            );
+            "0.0.0".to_string()
+        });
+
+        if self.source != VersionSource::Git {
+            return version;
+        }
+
+        // A tag that already is a post or dev release keeps its own suffix
+        if self.distance > 0 && !version.contains(".post") && !version.contains(".dev") {
+            version.push_str(&format!(".post{}", self.distance));
+        }
+
+        let mut local = Vec::new();
+        if self.distance > 0 && !self.commit.is_empty() {
+            local.push(format!("g{}", self.commit));
+        }
+        if self.dirty {
+            local.push("dirty".to_string());
+        }
+        if !local.is_empty() {
+            version.push('+');
+            version.push_str(&local.join("."));
+        }
+
+        version
+    }
+
+    /// Renders the `version.py` module.
+    fn to_python(&self, pep440: &str, build_date: &str) -> String {
+        let py_bool = |b: bool| if b { "True" } else { "False" };
+        format!(
+            "# Generated by pyside-cli, do not edit.\n\
+             __version__ = {}\n\
+             __version_pep440__ = {}\n\
+             __git_describe__ = {}\n\
+             __git_distance__ = {}\n\
+             __git_commit__ = {}\n\
+             __git_dirty__ = {}\n\
+             __commit_date__ = {}\n\
+             {} = {}\n",
+            py_str(&self.version),
+            py_str(pep440),
+            py_str(&self.describe),
+            self.distance,
+            py_str(&self.commit),
+            py_bool(self.dirty),
+            py_str(&self.commit_date),
+            BUILD_DATE_KEY,
+            py_str(build_date),
+        )
+    }
+}
+
+/// Writes `version.py` into `resources_dir`.
+///
+/// The file is left alone when nothing but the build date would change,
+/// so that its modification time only moves with the version itself.
+pub fn write_version_py(
+    resources_dir: &Path,
+    config: &VersionConfig,
+    git: &Path,
+) -> Result<(), Errcode> {
+    let version_py = resources_dir.join("version.py");
+    let info = VersionInfo::resolve(config, Some(git))?;
+    let pep440 = info.pep440();
+    let content = info.to_python(&pep440, &build_date());
+
+    if let Ok(existing) = fs::read_to_string(&version_py)
+        && without_build_date(&existing) == without_build_date(&content)
+    {
+        log::debug!("{} is up to date.", version_py.display());
+        return Ok(());
+    }
+
+    fs::write(&version_py, content).map_err(|e| {
+        Errcode::GeneralError(GeneralErrorKind::WriteFileFailed {
+            path: version_py.clone(),
+            source: e,
+        })
+    })?;
+    log::info!("Version {} written to {}.", pep440, version_py.display());
+
+    Ok(())
+}
+
+/// Build time, `SOURCE_DATE_EPOCH` wins for reproducible builds.
+fn build_date() -> String {
+    let secs = source_date_epoch().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    });
+    format_utc_timestamp(secs)
+}
+
+/// Runs git and returns its trimmed stdout, `None` on failure or empty output.
+fn git_output(git: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new(git)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() { None } else { Some(text) }
+}
+
+/// Splits `git describe --long --dirty --always` output into
+/// (tag, distance, abbreviated hash, dirty).
+///
+/// Without tags git prints only the hash, e.g. `abc1234-dirty`.
+fn parse_describe(describe: &str) -> (Option<&str>, u32, &str, bool) {
+    let (describe, dirty) = match describe.strip_suffix("-dirty") {
+        Some(rest) => (rest, true),
+        None => (describe, false),
+    };
+
+    // Tags may contain `-` themselves, so split from the right
+    let mut parts = describe.rsplitn(3, '-');
+    if let (Some(hash), Some(distance), Some(tag)) = (parts.next(), parts.next(), parts.next())
+        && let Some(hash) = hash.strip_prefix('g')
+        && let Ok(distance) = distance.parse()
+    {
+        return (Some(tag), distance, hash, dirty);
+    }
+
+    (None, 0, describe, dirty)
+}
+
+/// Normalizes a tag such as `v1.2.0-RC.1` to its PEP 440 form `1.2.0rc1`.
+///
+/// Returns `None` for versions that cannot be expressed in PEP 440.
+/// Ref: https://packaging.python.org/en/latest/specifications/version-specifiers/
+fn normalize_pep440(version: &str) -> Option<String> {
+    const PRE: &[(&str, &str)] = &[
+        ("preview", "rc"),
+        ("alpha", "a"),
+        ("beta", "b"),
+        ("pre", "rc"),
+        ("rc", "rc"),
+        ("a", "a"),
+        ("b", "b"),
+        ("c", "rc"),
+    ];
+    const POST: &[&str] = &["post", "rev", "r"];
+
+    let version = version.trim().to_ascii_lowercase();
+    let rest = version.strip_prefix('v').unwrap_or(&version);
+
+    let release_len = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let release = rest[..release_len]
+        .trim_end_matches('.')
+        .split('.')
+        .map(|n| n.parse::<u64>().ok().map(|n| n.to_string()))
+        .collect::<Option<Vec<_>>>()?;
+    let mut normalized = release.join(".");
+    let mut rest = &rest[release_len..];
+
+    if let Some((label, short)) = PRE
+        .iter()
+        .find(|(label, _)| strip_sep(rest).starts_with(label))
+    {
+        let (number, tail) = take_number(strip_sep(&strip_sep(rest)[label.len()..]));
+        normalized.push_str(&format!("{}{}", short, number.unwrap_or(0)));
+        rest = tail;
+    }
+
+    if let Some(label) = POST
+        .iter()
+        .find(|label| strip_sep(rest).starts_with(*label))
+    {
+        let (number, tail) = take_number(strip_sep(&strip_sep(rest)[label.len()..]));
+        normalized.push_str(&format!(".post{}", number.unwrap_or(0)));
+        rest = tail;
+    } else if let Some(tail) = rest.strip_prefix('-')
+        && let (Some(number), tail) = take_number(tail)
+    {
+        // `1.0-1` is shorthand for `1.0.post1`
+        normalized.push_str(&format!(".post{}", number));
+        rest = tail;
+    }
+
+    if let Some(tail) = strip_sep(rest).strip_prefix("dev") {
+        let (number, tail) = take_number(strip_sep(tail));
+        normalized.push_str(&format!(".dev{}", number.unwrap_or(0)));
+        rest = tail;
+    }
+
+    if rest.is_empty() {
+        Some(normalized)
+    } else {
+        None
+    }
+}
+
+fn strip_sep(s: &str) -> &str {
+    s.trim_start_matches(['-', '_', '.'])
+}
+
+/// Splits leading digits off `s`.
+fn take_number(s: &str) -> (Option<u64>, &str) {
+    let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    (s[..len].parse().ok(), &s[len..])
+}
+
+fn without_build_date(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with(BUILD_DATE_KEY))
+        .collect()
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn test_parse_describe() {
+        assert_eq!(
+            parse_describe("v1.2.0-3-gabc1234-dirty"),
+            (Some("v1.2.0"), 3, "abc1234", true)
+        );
+        assert_eq!(
+            parse_describe("release-1.0-rc1-0-gabc1234"),
+            (Some("release-1.0-rc1"), 0, "abc1234", false)
+        );
+        assert_eq!(parse_describe("abc1234-dirty"), (None, 0, "abc1234", true));
+        assert_eq!(parse_describe(""), (None, 0, "", false));
+    }
+
+    #[test]
+    fn test_pep440() {
+        assert_eq!(normalize_pep440("v1.2.0").as_deref(), Some("1.2.0"));
+        assert_eq!(normalize_pep440("1.2.0-RC.1").as_deref(), Some("1.2.0rc1"));
+        assert_eq!(normalize_pep440("1.0-alpha").as_deref(), Some("1.0a0"));
+        assert_eq!(normalize_pep440("1.0-1").as_deref(), Some("1.0.post1"));
+        assert_eq!(
+            normalize_pep440("2.0b2.post1.dev3").as_deref(),
+            Some("2.0b2.post1.dev3")
+        );
+        assert_eq!(normalize_pep440("01.002").as_deref(), Some("1.2"));
+        assert_eq!(normalize_pep440("release-2024"), None);
+        assert_eq!(normalize_pep440("1.0-foo"), None);
+
+        let info = VersionInfo {
+            source: VersionSource::Git,
+            version: "v1.2.0".to_string(),
+            describe: "v1.2.0-3-gabc1234-dirty".to_string(),
+            distance: 3,
+            commit: "abc1234".to_string(),
+            dirty: true,
+            commit_date: String::new(),
+        };
+        assert_eq!(info.pep440(), "1.2.0.post3+gabc1234.dirty");
+
+        let info = VersionInfo {
+            source: VersionSource::Env,
+            version: "1.3".to_string(),
+            ..info
+        };
+        assert_eq!(info.pep440(), "1.3");
+    }
+
+    #[test]
+    fn test_version_from_tag() {
+        let mut config = VersionConfig {
+            strip_prefix: Some("v".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.version_from_tag("v1.2.3"), "1.2.3");
+        assert_eq!(config.version_from_tag("1.2.3"), "1.2.3");
+
+        config.strip_prefix = None;
+        config.tag_regex = Some(Regex::new(r"^release-(\d+(\.\d+)*)$").unwrap());
+        assert_eq!(config.version_from_tag("release-2.0"), "2.0");
+    }
+
+    #[test]
+    fn test_env_fallback() {
+        let mut config = VersionConfig {
+            source: VersionSource::Env,
+            env: Some("PYSIDE_CLI_TEST_UNSET_VERSION".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            VersionInfo::resolve(&config, None),
+            Err(Errcode::GeneralError(
+                GeneralErrorKind::VersionEnvNotSet { .. }
+            ))
+        ));
+
+        config.fallback = Some("1.0".to_string());
+        assert_eq!(VersionInfo::resolve(&config, None).unwrap().version, "1.0");
+    }
+
+    #[test]
+    fn test_format_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc_timestamp(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc_timestamp(1714564800), "2024-05-01T12:00:00Z");
+    }
+}